@@ -1,28 +1,24 @@
-use ffmml::{Music, MusicPlayer};
+use ffmml::Music;
 use pagurus::{
-    audio::{AudioData, Sample},
+    audio::{AudioPump, BusId, Mixer, Sample},
     event::{Event, TimeoutTag},
     System,
 };
-use std::time::Duration;
 
 const SAMPLE_RATE: u16 = 48000;
 const DATA_SAMPLES: usize = 960; // 20ms
 const TIMEOUT_TAG: TimeoutTag = TimeoutTag::new(1);
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct AudioMixer {
-    start_time: Duration,
-    total_samples: u64,
-    audio_data: AudioData,
-    musics: Vec<MusicPlayer>,
+    pump: AudioPump,
+    mixer: Mixer,
 }
 
 impl AudioMixer {
     pub fn init<S: System>(&mut self, system: &mut S) {
-        self.start_time = system.clock_game_time();
-        self.audio_data = AudioData::new(system.audio_init(SAMPLE_RATE, DATA_SAMPLES));
-        system.clock_set_timeout(TIMEOUT_TAG, Duration::from_secs(0));
+        let spec = self.pump.start(system, SAMPLE_RATE, 1, DATA_SAMPLES);
+        self.mixer = Mixer::new(spec);
     }
 
     pub fn play_click_sound(&mut self) {
@@ -41,41 +37,27 @@ impl AudioMixer {
     }
 
     fn play(&mut self, music: &Music) {
-        self.musics.push(music.play(SAMPLE_RATE));
+        let source = music.play(SAMPLE_RATE).map(|x| Sample::F32(x.get()));
+        self.mixer.play(BusId::DEFAULT, source);
     }
 
     pub fn handle_event<S: System>(&mut self, system: &mut S, event: &Event) {
-        if !matches!(event, Event::Timeout(TIMEOUT_TAG)) {
-            return;
-        }
-
-        if self.musics.is_empty() {
-            self.total_samples += DATA_SAMPLES as u64;
-        } else {
-            for i in 0..DATA_SAMPLES {
-                let sample = self.sample();
-                self.audio_data.write_sample(i, sample);
+        let mixer = &mut self.mixer;
+        self.pump.handle_event(system, event, |data| {
+            if mixer.is_empty() {
+                return false;
             }
-            system.audio_enqueue(self.audio_data.as_ref());
-        }
-
-        let elapsed = Duration::from_secs(self.total_samples) / u32::from(SAMPLE_RATE);
-        let wait = elapsed.saturating_sub(system.clock_game_time());
-        system.clock_set_timeout(TIMEOUT_TAG, wait);
+            mixer.mix(data);
+            true
+        });
     }
+}
 
-    fn sample(&mut self) -> Sample {
-        let mut sample = ffmml::Sample::ZERO;
-        let mut i = 0;
-        while i < self.musics.len() {
-            if let Some(x) = self.musics[i].next() {
-                sample = sample + x;
-                i += 1;
-            } else {
-                self.musics.swap_remove(i);
-            }
+impl Default for AudioMixer {
+    fn default() -> Self {
+        Self {
+            pump: AudioPump::new(TIMEOUT_TAG),
+            mixer: Mixer::default(),
         }
-        self.total_samples += 1;
-        Sample::F32(sample.get())
     }
 }
@@ -32,6 +32,9 @@ impl<S: System + 'static> Game<S> for SnakeGame {
         // Audio.
         self.mixer.init(system);
 
+        // High score.
+        self.high_score = HighScore::load(system).or_fail()?;
+
         // Assets.
         let start = system.clock_game_time();
         self.assets = Some(Assets::load().or_fail()?);
@@ -61,9 +64,13 @@ impl<S: System + 'static> Game<S> for SnakeGame {
         let event = self.logical_window.handle_event(event);
         self.mixer.handle_event(system, &event);
 
-        if let Event::WindowResized(_) = event {
-            self.render(system).or_fail()?;
-            return Ok(true);
+        match event {
+            Event::WindowResized(_) => {
+                self.render(system).or_fail()?;
+                return Ok(true);
+            }
+            Event::TerminateRequested => return Ok(false),
+            _ => {}
         }
 
         let mut env = Env::new(
@@ -108,3 +115,73 @@ impl SnakeGame {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pagurus::event::{Key, KeyEvent};
+    use pagurus::headless::{HeadlessSystem, HeadlessSystemOptions};
+    use pagurus::record::{Player, Recorder};
+    use std::time::Duration;
+
+    #[test]
+    fn play_until_game_over_works() -> Result<()> {
+        let mut system = HeadlessSystem::new();
+        let mut game = SnakeGame::default();
+        game.initialize(&mut system).or_fail()?;
+
+        let event = Event::WindowResized(WINDOW_SIZE);
+        game.handle_event(&mut system, event).or_fail()?;
+        system.video_frame().is_some().or_fail()?;
+
+        for key in [Key::Up, Key::Return] {
+            let event = Event::Key(KeyEvent::from(key));
+            game.handle_event(&mut system, event).or_fail()?;
+        }
+        matches!(game.stage, Stage::Play(_)).or_fail()?;
+
+        while !matches!(game.stage, Stage::GameOver(_)) {
+            let event = system.advance_to_next_timeout().or_fail()?;
+            game.handle_event(&mut system, event).or_fail()?;
+            (system.clock_game_time().as_secs() < 10).or_fail()?;
+        }
+        (!system.audio_data().is_empty()).or_fail()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn record_and_replay_works() -> Result<()> {
+        let options = HeadlessSystemOptions {
+            unix_time: Duration::from_secs(1234567),
+            ..Default::default()
+        };
+        let mut recorder = Recorder::new(HeadlessSystem::with_options(options), Vec::new());
+        let mut game = SnakeGame::default();
+        recorder.initialize(&mut game).or_fail()?;
+
+        let event = Event::WindowResized(WINDOW_SIZE);
+        recorder.handle_event(&mut game, event).or_fail()?;
+        for key in [Key::Up, Key::Return] {
+            let event = Event::Key(KeyEvent::from(key));
+            recorder.handle_event(&mut game, event).or_fail()?;
+        }
+        for _ in 0..100 {
+            let event = recorder.inner_mut().advance_to_next_timeout().or_fail()?;
+            recorder.handle_event(&mut game, event).or_fail()?;
+        }
+        let (system, records) = recorder.into_inner();
+
+        let mut player = Player::new(HeadlessSystem::new(), records.as_slice()).or_fail()?;
+        let mut replayed_game = SnakeGame::default();
+        player.initialize(&mut replayed_game).or_fail()?;
+        player.play(&mut replayed_game).or_fail()?;
+        player.is_finished().or_fail()?;
+
+        let expected = system.video_frame().or_fail()?;
+        let actual = player.inner().video_frame().or_fail()?;
+        (expected.data() == actual.data()).or_fail()?;
+
+        Ok(())
+    }
+}
@@ -12,15 +12,19 @@ fn main() -> pagurus::Result<()> {
     let mut system = TuiSystem::new().or_fail()?;
     let mut game = SnakeGame::default();
     game.initialize(&mut system).or_fail()?;
-    while let Ok(event) = system.next_event() {
+    while let Ok(mut event) = system.next_event() {
         if matches!(event, Event::Key(KeyEvent { key: Key::Esc, .. })) {
-            break;
+            event = Event::TerminateRequested;
         }
         if !game.handle_event(&mut system, event).or_fail()? {
             break;
         }
     }
-    Ok(())
+
+    // Restore the terminal before exiting.
+    let exit_code = <SnakeGame as Game<TuiSystem>>::exit_code(&game);
+    std::mem::drop(system);
+    std::process::exit(exit_code);
 }
 
 fn file_println(msg: &str) {
@@ -139,12 +139,17 @@ impl PlayStage {
     ) -> Result<HandleEventResult> {
         match event {
             Event::Key(event) => self.handle_key_event(env, event).or_fail()?,
-            Event::Mouse(event) => self.handle_mouse_event(env, event).or_fail()?,
+            Event::Pointer(event) => {
+                if let Some(event) = event.to_mouse_event() {
+                    self.handle_mouse_event(env, event).or_fail()?;
+                }
+            }
+            #[allow(clippy::collapsible_match)]
             Event::Timeout(event) => {
                 if !self.handle_timeout_event(env, event).or_fail()? {
                     env.mixer.play_crash_sound();
 
-                    let stage = GameOverStage::new(self.game_state.clone(), env);
+                    let stage = GameOverStage::new(self.game_state.clone(), env).or_fail()?;
                     return Ok(HandleEventResult::NextStage(Stage::GameOver(stage)));
                 }
             }
@@ -257,7 +262,7 @@ pub struct GameOverStage {
 }
 
 impl GameOverStage {
-    fn new<S: System>(game_state: GameState, env: &mut Env<S>) -> Self {
+    fn new<S: System>(game_state: GameState, env: &mut Env<S>) -> Result<Self> {
         if game_state.score() > env.high_score.0 {
             pagurus::println!(
                 "high score was updated: {} => {}",
@@ -265,11 +270,12 @@ impl GameOverStage {
                 game_state.score()
             );
             env.high_score.0 = game_state.score();
+            env.high_score.save(env.system).or_fail()?;
         }
 
         let x = (WINDOW_SIZE.width / 2 - Button::SIZE.width / 2) as i32;
         let y = (WINDOW_SIZE.height / 2 + 14) as i32;
-        Self {
+        Ok(Self {
             game_state,
             retry_button: ButtonWidget::new(
                 env.assets.sprites.buttons.retry.clone(),
@@ -279,7 +285,7 @@ impl GameOverStage {
                 env.assets.sprites.buttons.title.clone(),
                 Position::from_xy(x, y + 44),
             ),
-        }
+        })
     }
 
     fn handle_event<S: System>(
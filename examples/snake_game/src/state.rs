@@ -1,5 +1,7 @@
+use orfail::OrFail;
 use pagurus::random::StdRng;
 use pagurus::spatial::{Contains, Position, Size};
+use pagurus::{Result, System};
 use rand::Rng;
 use std::collections::VecDeque;
 use std::time::Duration;
@@ -7,6 +9,19 @@ use std::time::Duration;
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct HighScore(pub u8);
 
+impl HighScore {
+    const STORAGE_KEY: &'static str = "highScore";
+
+    pub fn load<S: System>(system: &S) -> Result<Self> {
+        let value = system.storage_get(Self::STORAGE_KEY).or_fail()?;
+        Ok(Self(value.and_then(|v| v.first().copied()).unwrap_or(0)))
+    }
+
+    pub fn save<S: System>(&self, system: &mut S) -> Result<()> {
+        system.storage_put(Self::STORAGE_KEY, &[self.0]).or_fail()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GameState {
     pub snake: Snake,
@@ -2,7 +2,7 @@ use crate::assets::Button;
 use crate::state::Direction;
 use crate::{assets, Env};
 use orfail::OrFail;
-use pagurus::event::{Event, Key, KeyEvent, MouseEvent};
+use pagurus::event::{Event, Key, KeyEvent, MouseButton, MouseEvent};
 use pagurus::image::Canvas;
 use pagurus::spatial::{Contains, Position, Region};
 use pagurus::{Result, System};
@@ -94,7 +94,10 @@ impl ButtonWidget {
     pub fn handle_event<S: System>(&mut self, env: &mut Env<S>, event: &Event) -> Result<bool> {
         match event {
             // Event::Key(_) => todo!(),
-            Event::Mouse(event) => self.handle_mouse_event(env, event).or_fail(),
+            Event::Pointer(event) => match event.to_mouse_event() {
+                Some(event) => self.handle_mouse_event(env, &event).or_fail(),
+                None => Ok(false),
+            },
             //Event::Touch(_) => todo!(),
             _ => Ok(false),
         }
@@ -111,26 +114,29 @@ impl ButtonWidget {
                 .sprite
                 .normal
                 .get_pixel(pos - self.position)
-                .map_or(false, |p| p.a != 0))
+                .is_some_and(|p| p.a != 0))
         {
             env.change_state(&mut self.state, ButtonState::Normal);
             return Ok(false);
         }
 
         match event {
-            MouseEvent::Move { .. } => {
-                if self.state != ButtonState::Pressed {
-                    env.change_state(&mut self.state, ButtonState::Focused);
-                }
+            MouseEvent::Move { .. } if self.state != ButtonState::Pressed => {
+                env.change_state(&mut self.state, ButtonState::Focused);
             }
-            MouseEvent::Down { .. } => {
+            MouseEvent::Down {
+                button: MouseButton::Left,
+                ..
+            } => {
                 env.change_state(&mut self.state, ButtonState::Pressed);
             }
-            MouseEvent::Up { .. } => {
-                if self.state == ButtonState::Pressed {
-                    env.change_state(&mut self.state, ButtonState::Clicked);
-                }
+            MouseEvent::Up {
+                button: MouseButton::Left,
+                ..
+            } if self.state == ButtonState::Pressed => {
+                env.change_state(&mut self.state, ButtonState::Clicked);
             }
+            _ => {}
         }
 
         Ok(true)
@@ -182,6 +188,7 @@ impl CursorWidget {
 
     pub fn handle_event<S: System>(&mut self, env: &mut Env<S>, event: MouseEvent) -> Result<()> {
         match event {
+            #[allow(clippy::collapsible_match)]
             MouseEvent::Move { .. } => {
                 if !matches!(self.state, CursorState::Normal) {
                     let delta = event.position() - self.position;
@@ -201,11 +208,17 @@ impl CursorWidget {
                     return Ok(());
                 }
             }
-            MouseEvent::Down { .. } if matches!(self.state, CursorState::Normal) => {
+            MouseEvent::Down {
+                button: MouseButton::Left,
+                ..
+            } if matches!(self.state, CursorState::Normal) => {
                 env.change_state(&mut self.state, CursorState::Pressing);
                 self.direction = None;
             }
-            MouseEvent::Up { .. } => {
+            MouseEvent::Up {
+                button: MouseButton::Left,
+                ..
+            } => {
                 self.direction = match self.state {
                     CursorState::Up => Some(Direction::Up),
                     CursorState::Down => Some(Direction::Down),
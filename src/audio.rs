@@ -1,5 +1,25 @@
 use crate::Result;
-use orfail::Failure;
+use orfail::{Failure, OrFail};
+
+pub mod effect;
+#[cfg(feature = "mixer")]
+mod mixer;
+mod pump;
+#[cfg(feature = "wav")]
+mod recorder;
+mod resample;
+pub mod synth;
+#[cfg(feature = "wav")]
+mod wav;
+
+#[cfg(feature = "mixer")]
+pub use self::mixer::{BusId, Mixer, VoiceId};
+pub use self::pump::AudioPump;
+#[cfg(feature = "wav")]
+pub use self::recorder::AudioRecorder;
+pub use self::resample::{Resample, ResampleQuality};
+#[cfg(feature = "wav")]
+pub use self::wav::{Sound, WavWriter};
 
 #[derive(Debug, Default)]
 pub struct AudioData<B = Vec<u8>> {
@@ -11,7 +31,7 @@ impl AudioData<Vec<u8>> {
     pub fn new(spec: AudioSpec) -> Self {
         Self {
             spec,
-            data: vec![0; spec.data_samples * spec.sample_format.bytes()],
+            data: vec![0; spec.data_bytes()],
         }
     }
 
@@ -34,6 +54,23 @@ impl AudioData<Vec<u8>> {
         }
     }
 
+    // Writes the `i`-th interleaved frame.
+    // If `frame` has fewer samples than the channels, its last sample is repeated (e.g., mono to stereo).
+    #[inline]
+    pub fn write_frame<T>(&mut self, i: usize, frame: &[T])
+    where
+        T: Copy + Into<Sample>,
+    {
+        let Some(&last) = frame.last() else {
+            return;
+        };
+        let channels = usize::from(self.spec.channels);
+        for c in 0..channels {
+            let sample = frame.get(c).copied().unwrap_or(last);
+            self.write_sample(i * channels + c, sample);
+        }
+    }
+
     pub fn bytes_mut(&mut self) -> &mut [u8] {
         self.data.as_mut()
     }
@@ -47,23 +84,75 @@ impl AudioData<Vec<u8>> {
 }
 
 impl<B: AsRef<[u8]>> AudioData<B> {
+    pub fn with_data(spec: AudioSpec, data: B) -> Result<Self> {
+        (data.as_ref().len() == spec.data_bytes()).or_fail()?;
+        Ok(Self { spec, data })
+    }
+
     pub fn spec(&self) -> AudioSpec {
         self.spec
     }
 
-    pub fn samples(&self) -> Samples {
+    pub fn samples(&self) -> Samples<'_> {
         Samples {
             spec: self.spec,
             data: self.data.as_ref(),
         }
     }
 
+    pub fn frames(&self) -> Frames<'_> {
+        Frames {
+            spec: self.spec,
+            data: self.data.as_ref(),
+        }
+    }
+
     pub fn bytes(&self) -> &[u8] {
         self.data.as_ref()
     }
+
+    // Converts the sample format, the channels and the sample rate into those of `spec`.
+    //
+    // `spec.data_samples` is ignored as the number of the resulting frames depends on the sample rates.
+    // Multi-channel audio is averaged when converted to mono, and the last channel is repeated when
+    // the number of channels increases.
+    pub fn convert(&self, spec: AudioSpec) -> AudioData {
+        let from_channels = usize::from(self.spec.channels.max(1));
+        let to_channels = usize::from(spec.channels.max(1));
+        let channels = (0..from_channels)
+            .map(|c| {
+                let samples = self.samples().skip(c).step_by(from_channels);
+                if self.spec.sample_rate == spec.sample_rate {
+                    samples.collect::<Vec<_>>()
+                } else {
+                    let (from, to) = (self.spec.sample_rate, spec.sample_rate);
+                    Resample::new(samples, from, to, ResampleQuality::Sinc).collect()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let frames = channels[0].len();
+        let mut data = AudioData::new(AudioSpec {
+            data_samples: frames,
+            channels: to_channels as u8,
+            ..spec
+        });
+        for i in 0..frames {
+            if to_channels == 1 && from_channels > 1 {
+                let sum = channels.iter().map(|c| c[i].to_f32()).sum::<f32>();
+                data.write_sample(i, sum / from_channels as f32);
+                continue;
+            }
+            for c in 0..to_channels {
+                let sample = channels[c.min(from_channels - 1)][i];
+                data.write_sample(i * to_channels + c, sample);
+            }
+        }
+        data
+    }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize),
@@ -72,11 +161,33 @@ impl<B: AsRef<[u8]>> AudioData<B> {
 pub struct AudioSpec {
     pub sample_format: SampleFormat,
     pub sample_rate: u16,
+
+    // Number of frames (i.e., samples per channel)
     pub data_samples: usize,
+
+    #[cfg_attr(feature = "serde", serde(default = "AudioSpec::default_channels"))]
+    pub channels: u8,
 }
 
 impl AudioSpec {
-    pub const CHANNELS: u8 = 1;
+    pub const fn data_bytes(self) -> usize {
+        self.data_samples * self.channels as usize * self.sample_format.bytes()
+    }
+
+    const fn default_channels() -> u8 {
+        1
+    }
+}
+
+impl Default for AudioSpec {
+    fn default() -> Self {
+        Self {
+            sample_format: SampleFormat::default(),
+            sample_rate: 0,
+            data_samples: 0,
+            channels: Self::default_channels(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -87,7 +198,7 @@ pub struct Samples<'a> {
 
 impl<'a> Samples<'a> {
     pub fn len(&self) -> usize {
-        self.spec.data_samples
+        self.data.len() / self.spec.sample_format.bytes()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -130,6 +241,45 @@ impl<'a> Iterator for Samples<'a> {
     }
 }
 
+// Iterator over interleaved frames (each item yields the samples of all channels)
+#[derive(Debug)]
+pub struct Frames<'a> {
+    spec: AudioSpec,
+    data: &'a [u8],
+}
+
+impl<'a> Frames<'a> {
+    pub fn len(&self) -> usize {
+        self.data.len() / self.frame_bytes().max(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn frame_bytes(&self) -> usize {
+        usize::from(self.spec.channels) * self.spec.sample_format.bytes()
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Samples<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.frame_bytes();
+        if n == 0 || self.data.len() < n {
+            return None;
+        }
+
+        let (frame, rest) = self.data.split_at(n);
+        self.data = rest;
+        Some(Samples {
+            spec: self.spec,
+            data: frame,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
@@ -217,3 +367,54 @@ impl From<f32> for Sample {
         Self::F32(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_works() {
+        let formats = [
+            SampleFormat::I16Be,
+            SampleFormat::I16Le,
+            SampleFormat::F32Be,
+            SampleFormat::F32Le,
+        ];
+        let spec = AudioSpec {
+            sample_format: SampleFormat::I16Le,
+            sample_rate: 100,
+            data_samples: 3,
+            channels: 2,
+        };
+        let mut data = AudioData::new(spec);
+        for (i, x) in [0, 0, i16::MAX, i16::MIN, -100, 100]
+            .into_iter()
+            .enumerate()
+        {
+            data.write_sample(i, x);
+        }
+
+        for format in formats {
+            let spec = AudioSpec {
+                sample_format: format,
+                ..spec
+            };
+            let converted = data.convert(spec).convert(data.spec());
+            assert_eq!(converted.bytes(), data.bytes(), "{format:?}");
+        }
+
+        let mono = data.convert(AudioSpec {
+            channels: 1,
+            ..spec
+        });
+        let samples = mono.samples().map(|x| x.to_i16()).collect::<Vec<_>>();
+        assert_eq!(samples, [0, 0, 0]);
+
+        let resampled = data.convert(AudioSpec {
+            sample_rate: 200,
+            ..spec
+        });
+        assert_eq!(resampled.spec().data_samples, 6);
+        assert_eq!(resampled.spec().channels, 2);
+    }
+}
@@ -0,0 +1,695 @@
+// Audio effects applied to mono samples (e.g., the output of `Mixer`).
+//
+// Multi-channel data needs one effect instance per channel (see `apply()`).
+use super::{AudioData, Sample};
+use std::f32::consts::PI;
+use std::time::Duration;
+
+// Time taken for parameter changes to reach their targets (to avoid clicks)
+pub const SMOOTHING_TIME: Duration = Duration::from_millis(20);
+
+pub trait Effect {
+    fn process(&mut self, sample: Sample) -> Sample;
+
+    // Processes the samples of `channel` in `data` in place (the other channels are left untouched).
+    fn apply_channel(&mut self, data: &mut AudioData, channel: u8) {
+        let channels = usize::from(data.spec().channels.max(1));
+        let channel = usize::from(channel);
+        if channel >= channels {
+            return;
+        }
+
+        let inputs = data
+            .samples()
+            .skip(channel)
+            .step_by(channels)
+            .collect::<Vec<_>>();
+        for (i, x) in inputs.into_iter().enumerate() {
+            let y = self.process(x);
+            data.write_sample(i * channels + channel, y);
+        }
+    }
+
+    // Connects `next` after this effect.
+    fn then<E: Effect>(self, next: E) -> Chain<Self, E>
+    where
+        Self: Sized,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+// Processes each channel of `data` in place with its own effect instance (`effects[i]` for the channel `i`).
+//
+// Channels without a corresponding instance are left untouched.
+pub fn apply<E: Effect>(effects: &mut [E], data: &mut AudioData) {
+    let channels = usize::from(data.spec().channels);
+    for (channel, effect) in effects.iter_mut().take(channels).enumerate() {
+        effect.apply_channel(data, channel as u8);
+    }
+}
+
+impl<E: Effect + ?Sized> Effect for Box<E> {
+    fn process(&mut self, sample: Sample) -> Sample {
+        (**self).process(sample)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Chain<A, B> {
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+}
+
+impl<A: Effect, B: Effect> Effect for Chain<A, B> {
+    fn process(&mut self, sample: Sample) -> Sample {
+        self.second.process(self.first.process(sample))
+    }
+}
+
+// Parameter that changes linearly to its target value.
+#[derive(Debug, Clone, Copy)]
+pub struct Smoothed {
+    value: f32,
+    target: f32,
+    delta: f32,
+    remaining_frames: u32,
+}
+
+impl Smoothed {
+    pub fn new(value: f32) -> Self {
+        Self {
+            value,
+            target: value,
+            delta: 0.0,
+            remaining_frames: 0,
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn is_smoothing(&self) -> bool {
+        self.remaining_frames > 0
+    }
+
+    pub fn set(&mut self, target: f32, frames: u32) {
+        if frames == 0 {
+            self.set_immediately(target);
+            return;
+        }
+        self.target = target;
+        self.delta = (target - self.value) / frames as f32;
+        self.remaining_frames = frames;
+    }
+
+    pub fn set_immediately(&mut self, value: f32) {
+        *self = Self::new(value);
+    }
+
+    // Advances one frame and returns the new value.
+    pub fn next_value(&mut self) -> f32 {
+        if self.remaining_frames > 1 {
+            self.remaining_frames -= 1;
+            self.value += self.delta;
+        } else if self.remaining_frames == 1 {
+            self.remaining_frames = 0;
+            self.value = self.target;
+        }
+        self.value
+    }
+}
+
+fn smoothing_frames(sample_rate: f32) -> u32 {
+    (SMOOTHING_TIME.as_secs_f32() * sample_rate) as u32
+}
+
+// One-pole low-pass filter
+#[derive(Debug, Clone)]
+pub struct LowPass {
+    sample_rate: f32,
+    cutoff: Smoothed,
+    coeff: f32,
+    y: f32,
+}
+
+impl LowPass {
+    pub fn new(sample_rate: u16, cutoff: f32) -> Self {
+        let sample_rate = f32::from(sample_rate.max(1));
+        Self {
+            sample_rate,
+            cutoff: Smoothed::new(cutoff),
+            coeff: one_pole_coeff(cutoff, sample_rate),
+            y: 0.0,
+        }
+    }
+
+    pub fn cutoff(&self) -> f32 {
+        self.cutoff.target()
+    }
+
+    pub fn set_cutoff(&mut self, cutoff: f32) {
+        self.cutoff.set(cutoff, smoothing_frames(self.sample_rate));
+    }
+
+    fn next_value(&mut self, x: f32) -> f32 {
+        if self.cutoff.is_smoothing() {
+            self.coeff = one_pole_coeff(self.cutoff.next_value(), self.sample_rate);
+        }
+        self.y += self.coeff * (x - self.y);
+        self.y
+    }
+}
+
+impl Effect for LowPass {
+    fn process(&mut self, sample: Sample) -> Sample {
+        Sample::F32(self.next_value(sample.to_f32()))
+    }
+}
+
+// One-pole high-pass filter
+#[derive(Debug, Clone)]
+pub struct HighPass {
+    low_pass: LowPass,
+}
+
+impl HighPass {
+    pub fn new(sample_rate: u16, cutoff: f32) -> Self {
+        Self {
+            low_pass: LowPass::new(sample_rate, cutoff),
+        }
+    }
+
+    pub fn cutoff(&self) -> f32 {
+        self.low_pass.cutoff()
+    }
+
+    pub fn set_cutoff(&mut self, cutoff: f32) {
+        self.low_pass.set_cutoff(cutoff);
+    }
+}
+
+impl Effect for HighPass {
+    fn process(&mut self, sample: Sample) -> Sample {
+        let x = sample.to_f32();
+        Sample::F32(x - self.low_pass.next_value(x))
+    }
+}
+
+fn one_pole_coeff(cutoff: f32, sample_rate: f32) -> f32 {
+    let cutoff = cutoff.clamp(0.0, sample_rate / 2.0);
+    1.0 - (-2.0 * PI * cutoff / sample_rate).exp()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BiquadKind {
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch,
+    Peak,
+    LowShelf,
+    HighShelf,
+}
+
+// Biquad filter (see: "Cookbook formulae for audio EQ biquad filter coefficients" by Robert Bristow-Johnson)
+#[derive(Debug, Clone)]
+pub struct Biquad {
+    kind: BiquadKind,
+    sample_rate: f32,
+    frequency: Smoothed,
+    q: Smoothed,
+    gain_db: Smoothed,
+    coeffs: [f32; 5],
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    // `gain_db` is only used by `BiquadKind::{Peak, LowShelf, HighShelf}`.
+    pub fn new(sample_rate: u16, kind: BiquadKind, frequency: f32, q: f32, gain_db: f32) -> Self {
+        let mut this = Self {
+            kind,
+            sample_rate: f32::from(sample_rate.max(1)),
+            frequency: Smoothed::new(frequency),
+            q: Smoothed::new(q),
+            gain_db: Smoothed::new(gain_db),
+            coeffs: [0.0; 5],
+            z1: 0.0,
+            z2: 0.0,
+        };
+        this.update_coeffs();
+        this
+    }
+
+    pub fn kind(&self) -> BiquadKind {
+        self.kind
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency.target()
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency
+            .set(frequency, smoothing_frames(self.sample_rate));
+    }
+
+    pub fn q(&self) -> f32 {
+        self.q.target()
+    }
+
+    pub fn set_q(&mut self, q: f32) {
+        self.q.set(q, smoothing_frames(self.sample_rate));
+    }
+
+    pub fn gain_db(&self) -> f32 {
+        self.gain_db.target()
+    }
+
+    pub fn set_gain_db(&mut self, gain_db: f32) {
+        self.gain_db
+            .set(gain_db, smoothing_frames(self.sample_rate));
+    }
+
+    fn update_coeffs(&mut self) {
+        let frequency = self
+            .frequency
+            .value()
+            .clamp(1.0, (self.sample_rate / 2.0 - 1.0).max(1.0));
+        let w0 = 2.0 * PI * frequency / self.sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * self.q.value().max(0.01));
+        let a = 10f32.powf(self.gain_db.value() / 40.0);
+        let sq = 2.0 * a.sqrt() * alpha;
+
+        let (b0, b1, b2, a0, a1, a2) = match self.kind {
+            BiquadKind::LowPass => {
+                let b = (1.0 - cos) / 2.0;
+                (b, 1.0 - cos, b, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+            }
+            BiquadKind::HighPass => {
+                let b = (1.0 + cos) / 2.0;
+                (b, -(1.0 + cos), b, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+            }
+            BiquadKind::BandPass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BiquadKind::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BiquadKind::Peak => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            BiquadKind::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos + sq),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - sq),
+                (a + 1.0) + (a - 1.0) * cos + sq,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - sq,
+            ),
+            BiquadKind::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos + sq),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - sq),
+                (a + 1.0) - (a - 1.0) * cos + sq,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - sq,
+            ),
+        };
+        self.coeffs = [b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0];
+    }
+}
+
+impl Effect for Biquad {
+    fn process(&mut self, sample: Sample) -> Sample {
+        if self.frequency.is_smoothing() || self.q.is_smoothing() || self.gain_db.is_smoothing() {
+            self.frequency.next_value();
+            self.q.next_value();
+            self.gain_db.next_value();
+            self.update_coeffs();
+        }
+
+        // Transposed direct form II
+        let [b0, b1, b2, a1, a2] = self.coeffs;
+        let x = sample.to_f32();
+        let y = b0 * x + self.z1;
+        self.z1 = b1 * x - a1 * y + self.z2;
+        self.z2 = b2 * x - a2 * y;
+        Sample::F32(y)
+    }
+}
+
+// Feedback delay (echo)
+#[derive(Debug, Clone)]
+pub struct Delay {
+    sample_rate: f32,
+    buf: Vec<f32>,
+    write_index: usize,
+    delay_frames: Smoothed,
+    feedback: Smoothed,
+    mix: Smoothed,
+}
+
+impl Delay {
+    pub fn new(sample_rate: u16, max_delay: Duration) -> Self {
+        let sample_rate = f32::from(sample_rate.max(1));
+        // At least one frame, as a zero delay would read the oldest sample in the buffer.
+        let frames = (max_delay.as_secs_f32() * sample_rate).ceil().max(1.0) as usize;
+        Self {
+            sample_rate,
+            buf: vec![0.0; frames + 2],
+            write_index: 0,
+            delay_frames: Smoothed::new(frames as f32),
+            feedback: Smoothed::new(0.5),
+            mix: Smoothed::new(0.5),
+        }
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs_f32(self.delay_frames.target() / self.sample_rate)
+    }
+
+    // Clamped to `1 frame..=max_delay` (`max_delay` is the value given to `Delay::new()`).
+    pub fn set_delay(&mut self, delay: Duration) {
+        let max = (self.buf.len() - 2) as f32;
+        let frames = (delay.as_secs_f32() * self.sample_rate).clamp(1.0, max);
+        self.delay_frames
+            .set(frames, smoothing_frames(self.sample_rate));
+    }
+
+    pub fn feedback(&self) -> f32 {
+        self.feedback.target()
+    }
+
+    // Clamped to `0.0..=0.99` to keep the output stable.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback.set(
+            feedback.clamp(0.0, 0.99),
+            smoothing_frames(self.sample_rate),
+        );
+    }
+
+    pub fn mix(&self) -> f32 {
+        self.mix.target()
+    }
+
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix.set(mix, smoothing_frames(self.sample_rate));
+    }
+}
+
+impl Effect for Delay {
+    fn process(&mut self, sample: Sample) -> Sample {
+        let delay = self.delay_frames.next_value();
+        let feedback = self.feedback.next_value();
+        let mix = self.mix.next_value();
+
+        // Linear interpolation keeps delay time changes smooth.
+        let n = self.buf.len();
+        let read = (self.write_index as f32 + n as f32 - delay) % n as f32;
+        let i = read as usize;
+        let t = read.fract();
+        let delayed = self.buf[i] * (1.0 - t) + self.buf[(i + 1) % n] * t;
+
+        let x = sample.to_f32();
+        self.buf[self.write_index] = x + delayed * feedback;
+        self.write_index = (self.write_index + 1) % n;
+        Sample::F32(x + delayed * mix)
+    }
+}
+
+// Small Schroeder reverb (four parallel comb filters followed by two all-pass filters)
+#[derive(Debug, Clone)]
+pub struct Reverb {
+    sample_rate: f32,
+    combs: Vec<Comb>,
+    all_passes: Vec<AllPass>,
+    room_size: Smoothed,
+    damping: Smoothed,
+    mix: Smoothed,
+}
+
+impl Reverb {
+    // Tunings from Freeverb (for 44.1 kHz)
+    const COMB_FRAMES: [usize; 4] = [1116, 1188, 1277, 1356];
+    const ALL_PASS_FRAMES: [usize; 2] = [556, 441];
+    const INPUT_GAIN: f32 = 0.09;
+
+    pub fn new(sample_rate: u16) -> Self {
+        let sample_rate = f32::from(sample_rate.max(1));
+        let scale = |n: usize| ((n as f32 * sample_rate / 44100.0) as usize).max(1);
+        Self {
+            sample_rate,
+            combs: Self::COMB_FRAMES
+                .into_iter()
+                .map(|n| Comb::new(scale(n)))
+                .collect(),
+            all_passes: Self::ALL_PASS_FRAMES
+                .into_iter()
+                .map(|n| AllPass::new(scale(n)))
+                .collect(),
+            room_size: Smoothed::new(0.5),
+            damping: Smoothed::new(0.5),
+            mix: Smoothed::new(0.3),
+        }
+    }
+
+    pub fn room_size(&self) -> f32 {
+        self.room_size.target()
+    }
+
+    // `0.0..=1.0` (larger values make longer tails)
+    pub fn set_room_size(&mut self, room_size: f32) {
+        self.room_size.set(
+            room_size.clamp(0.0, 1.0),
+            smoothing_frames(self.sample_rate),
+        );
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping.target()
+    }
+
+    // `0.0..=1.0` (larger values absorb more high frequencies)
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping
+            .set(damping.clamp(0.0, 1.0), smoothing_frames(self.sample_rate));
+    }
+
+    pub fn mix(&self) -> f32 {
+        self.mix.target()
+    }
+
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix.set(mix, smoothing_frames(self.sample_rate));
+    }
+}
+
+impl Effect for Reverb {
+    fn process(&mut self, sample: Sample) -> Sample {
+        let feedback = 0.7 + 0.28 * self.room_size.next_value();
+        let damping = 0.4 * self.damping.next_value();
+        let mix = self.mix.next_value();
+
+        let x = sample.to_f32();
+        let input = x * Self::INPUT_GAIN;
+        let mut wet = self
+            .combs
+            .iter_mut()
+            .map(|c| c.process(input, feedback, damping))
+            .sum::<f32>();
+        for all_pass in &mut self.all_passes {
+            wet = all_pass.process(wet);
+        }
+        Sample::F32(x * (1.0 - mix) + wet * mix)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Comb {
+    buf: Vec<f32>,
+    index: usize,
+    filter: f32,
+}
+
+impl Comb {
+    fn new(frames: usize) -> Self {
+        Self {
+            buf: vec![0.0; frames],
+            index: 0,
+            filter: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f32, feedback: f32, damping: f32) -> f32 {
+        let y = self.buf[self.index];
+        self.filter = y * (1.0 - damping) + self.filter * damping;
+        self.buf[self.index] = x + self.filter * feedback;
+        self.index = (self.index + 1) % self.buf.len();
+        y
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AllPass {
+    buf: Vec<f32>,
+    index: usize,
+}
+
+impl AllPass {
+    const FEEDBACK: f32 = 0.5;
+
+    fn new(frames: usize) -> Self {
+        Self {
+            buf: vec![0.0; frames],
+            index: 0,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let delayed = self.buf[self.index];
+        self.buf[self.index] = x + delayed * Self::FEEDBACK;
+        self.index = (self.index + 1) % self.buf.len();
+        delayed - x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{AudioSpec, SampleFormat};
+
+    const SAMPLE_RATE: u16 = 8000;
+
+    fn sine(hz: f32) -> impl Iterator<Item = Sample> {
+        (0..).map(move |i| Sample::F32((2.0 * PI * hz * i as f32 / f32::from(SAMPLE_RATE)).sin()))
+    }
+
+    fn peak<E: Effect>(effect: &mut E, hz: f32) -> f32 {
+        sine(hz)
+            .take(usize::from(SAMPLE_RATE))
+            .map(|x| effect.process(x).to_f32().abs())
+            .skip(usize::from(SAMPLE_RATE) / 2)
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn filters_work() {
+        let mut low_pass = LowPass::new(SAMPLE_RATE, 200.0);
+        assert!(peak(&mut low_pass, 50.0) > 0.9);
+        assert!(peak(&mut low_pass, 2000.0) < 0.2);
+
+        let mut high_pass = HighPass::new(SAMPLE_RATE, 200.0);
+        assert!(peak(&mut high_pass, 50.0) < 0.3);
+        assert!(peak(&mut high_pass, 2000.0) > 0.9);
+
+        let mut band_pass = Biquad::new(SAMPLE_RATE, BiquadKind::BandPass, 1000.0, 2.0, 0.0);
+        assert!(peak(&mut band_pass, 1000.0) > 0.9);
+        assert!(peak(&mut band_pass, 100.0) < 0.2);
+
+        let mut peak_eq = Biquad::new(SAMPLE_RATE, BiquadKind::Peak, 1000.0, 1.0, 6.0);
+        assert!((peak(&mut peak_eq, 1000.0) - 2.0).abs() < 0.1);
+
+        // Degenerate sample rates (e.g., `AudioSpec::default()`) must not panic.
+        let mut biquad = Biquad::new(0, BiquadKind::LowPass, 1000.0, 1.0, 0.0);
+        biquad.process(Sample::F32(1.0));
+
+        // Changing parameters must not produce spikes.
+        low_pass.set_cutoff(3000.0);
+        assert!(peak(&mut low_pass, 2000.0) > 0.7);
+    }
+
+    #[test]
+    fn stereo_works() {
+        let spec = AudioSpec {
+            sample_format: SampleFormat::F32Le,
+            sample_rate: SAMPLE_RATE,
+            data_samples: 400,
+            channels: 2,
+        };
+        let mut data = AudioData::new(spec);
+        for i in 0..spec.data_samples {
+            data.write_frame(i, &[1.0f32, 0.0]);
+        }
+
+        // Panning must be preserved.
+        let mut effects = [
+            LowPass::new(SAMPLE_RATE, 1000.0),
+            LowPass::new(SAMPLE_RATE, 1000.0),
+        ];
+        apply(&mut effects, &mut data);
+        let last = data.frames().last().unwrap().collect::<Vec<_>>();
+        assert!((last[0].to_f32() - 1.0).abs() < 0.01);
+        assert_eq!(last[1].to_f32(), 0.0);
+
+        // Only the left channel is processed.
+        let mut delay = [Delay::new(SAMPLE_RATE, Duration::from_millis(10))];
+        let mut data = AudioData::new(spec);
+        data.write_frame(0, &[1.0f32, 1.0]);
+        apply(&mut delay, &mut data);
+        let samples = data.samples().map(|x| x.to_f32()).collect::<Vec<_>>();
+        assert_eq!(&samples[..2], &[1.0, 1.0]);
+        assert_eq!(samples[160], 0.5);
+        assert!(samples.iter().skip(3).step_by(2).all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn delay_and_reverb_work() {
+        let impulse = || (0..SAMPLE_RATE).map(|i| Sample::F32(if i == 0 { 1.0 } else { 0.0 }));
+
+        let mut delay = Delay::new(SAMPLE_RATE, Duration::from_millis(100));
+        let output = impulse()
+            .map(|x| delay.process(x).to_f32())
+            .collect::<Vec<_>>();
+        assert_eq!(output[0], 1.0);
+        assert_eq!(output[800], 0.5);
+        assert_eq!(output[1600], 0.25);
+        assert_eq!(output[1601], 0.0);
+
+        // Zero delays are treated as one frame (instead of the longest one).
+        let mut delay = Delay::new(SAMPLE_RATE, Duration::ZERO);
+        let output = impulse()
+            .map(|x| delay.process(x).to_f32())
+            .collect::<Vec<_>>();
+        assert_eq!(&output[..3], &[1.0, 0.5, 0.25]);
+
+        let mut delay = Delay::new(SAMPLE_RATE, Duration::from_millis(100));
+        delay.set_delay(Duration::ZERO);
+        let output = std::iter::repeat_n(Sample::F32(0.0), 200)
+            .chain(impulse())
+            .map(|x| delay.process(x).to_f32())
+            .skip(200)
+            .collect::<Vec<_>>();
+        assert_eq!(&output[..3], &[1.0, 0.5, 0.25]);
+        assert!(output[100..].iter().all(|x| x.abs() < 1e-6));
+
+        let mut fx = LowPass::new(SAMPLE_RATE, 1000.0).then(Reverb::new(SAMPLE_RATE));
+        fx.second_mut().set_room_size(1.0);
+        let output = impulse()
+            .map(|x| fx.process(x).to_f32())
+            .collect::<Vec<_>>();
+        assert!(output[1000..].iter().any(|&x| x != 0.0));
+        assert!(output.iter().all(|x| x.abs() <= 1.0));
+    }
+}
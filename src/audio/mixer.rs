@@ -0,0 +1,294 @@
+use super::{AudioData, AudioSpec, Sample};
+use std::time::Duration;
+
+type Source = Box<dyn Iterator<Item = Sample>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VoiceId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BusId(usize);
+
+impl BusId {
+    // The bus that always exists
+    pub const DEFAULT: Self = Self(0);
+}
+
+// Mixes mono sources (voices) into `AudioData`.
+//
+// Each voice belongs to a bus and the output gain of a voice is
+// `voice gain * bus gain * master gain`.
+pub struct Mixer {
+    spec: AudioSpec,
+    voices: Vec<Voice>,
+    bus_gains: Vec<f32>,
+    master_gain: f32,
+    soft_clip: bool,
+    next_voice_id: u64,
+}
+
+impl Mixer {
+    pub fn new(spec: AudioSpec) -> Self {
+        Self {
+            spec,
+            voices: Vec::new(),
+            bus_gains: vec![1.0],
+            master_gain: 1.0,
+            soft_clip: true,
+            next_voice_id: 0,
+        }
+    }
+
+    pub fn spec(&self) -> AudioSpec {
+        self.spec
+    }
+
+    pub fn add_bus(&mut self, gain: f32) -> BusId {
+        self.bus_gains.push(gain);
+        BusId(self.bus_gains.len() - 1)
+    }
+
+    pub fn bus_gain(&self, bus: BusId) -> f32 {
+        self.bus_gains.get(bus.0).copied().unwrap_or(0.0)
+    }
+
+    pub fn set_bus_gain(&mut self, bus: BusId, gain: f32) {
+        if let Some(x) = self.bus_gains.get_mut(bus.0) {
+            *x = gain;
+        }
+    }
+
+    pub fn master_gain(&self) -> f32 {
+        self.master_gain
+    }
+
+    pub fn set_master_gain(&mut self, gain: f32) {
+        self.master_gain = gain;
+    }
+
+    // Enabled by default. If disabled, the output is hard-clipped to [-1.0, 1.0] instead.
+    pub fn set_soft_clip(&mut self, enabled: bool) {
+        self.soft_clip = enabled;
+    }
+
+    pub fn play<I>(&mut self, bus: BusId, source: I) -> VoiceId
+    where
+        I: 'static + Iterator<Item = Sample>,
+    {
+        let id = VoiceId(self.next_voice_id);
+        self.next_voice_id += 1;
+        self.voices.push(Voice {
+            id,
+            bus,
+            source: Box::new(source),
+            gain: 1.0,
+            fade: None,
+        });
+        id
+    }
+
+    pub fn is_playing(&self, voice: VoiceId) -> bool {
+        self.voice(voice).is_some()
+    }
+
+    pub fn voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voices.is_empty()
+    }
+
+    pub fn set_gain(&mut self, voice: VoiceId, gain: f32) {
+        if let Some(voice) = self.voice_mut(voice) {
+            voice.gain = gain;
+            voice.fade = None;
+        }
+    }
+
+    pub fn stop(&mut self, voice: VoiceId) {
+        self.voices.retain(|v| v.id != voice);
+    }
+
+    pub fn stop_bus(&mut self, bus: BusId) {
+        self.voices.retain(|v| v.bus != bus);
+    }
+
+    pub fn stop_all(&mut self) {
+        self.voices.clear();
+    }
+
+    // Changes the gain of `voice` linearly to `gain` over `duration`.
+    pub fn fade(&mut self, voice: VoiceId, gain: f32, duration: Duration) {
+        self.start_fade(voice, gain, duration, false);
+    }
+
+    // Fades `voice` out and stops it at the end of the fade.
+    pub fn fade_out(&mut self, voice: VoiceId, duration: Duration) {
+        self.start_fade(voice, 0.0, duration, true);
+    }
+
+    // Overwrites all frames of `data` with the mixed output.
+    pub fn mix(&mut self, data: &mut AudioData) {
+        let channels = usize::from(data.spec().channels);
+        for i in 0..data.spec().data_samples {
+            let sample = self.next_sample();
+            for c in 0..channels {
+                data.write_sample(i * channels + c, sample);
+            }
+        }
+    }
+
+    fn next_sample(&mut self) -> f32 {
+        let mut sum = 0.0;
+        let mut i = 0;
+        while i < self.voices.len() {
+            let voice = &mut self.voices[i];
+            let Some(x) = voice.source.next() else {
+                self.voices.swap_remove(i);
+                continue;
+            };
+
+            let bus_gain = self.bus_gains.get(voice.bus.0).copied().unwrap_or(0.0);
+            sum += x.to_f32() * voice.gain * bus_gain;
+            if !voice.advance_fade() {
+                self.voices.swap_remove(i);
+                continue;
+            }
+            i += 1;
+        }
+
+        let sum = sum * self.master_gain;
+        if self.soft_clip {
+            sum.tanh()
+        } else {
+            sum.clamp(-1.0, 1.0)
+        }
+    }
+
+    fn start_fade(&mut self, voice: VoiceId, gain: f32, duration: Duration, stop: bool) {
+        let frames = (duration.as_secs_f64() * f64::from(self.spec.sample_rate)).round() as u64;
+        let Some(v) = self.voice_mut(voice) else {
+            return;
+        };
+        v.fade = Some(Fade {
+            delta: (gain - v.gain) / frames.max(1) as f32,
+            target: gain,
+            remaining_frames: frames,
+            stop,
+        });
+        if frames == 0 && !v.advance_fade() {
+            self.stop(voice);
+        }
+    }
+
+    fn voice(&self, id: VoiceId) -> Option<&Voice> {
+        self.voices.iter().find(|v| v.id == id)
+    }
+
+    fn voice_mut(&mut self, id: VoiceId) -> Option<&mut Voice> {
+        self.voices.iter_mut().find(|v| v.id == id)
+    }
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self::new(AudioSpec::default())
+    }
+}
+
+impl std::fmt::Debug for Mixer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mixer")
+            .field("spec", &self.spec)
+            .field("voices", &self.voices.len())
+            .field("bus_gains", &self.bus_gains)
+            .field("master_gain", &self.master_gain)
+            .field("soft_clip", &self.soft_clip)
+            .finish()
+    }
+}
+
+struct Voice {
+    id: VoiceId,
+    bus: BusId,
+    source: Source,
+    gain: f32,
+    fade: Option<Fade>,
+}
+
+impl Voice {
+    // Returns `false` if the voice should be stopped.
+    fn advance_fade(&mut self) -> bool {
+        let Some(fade) = &mut self.fade else {
+            return true;
+        };
+        if fade.remaining_frames > 1 {
+            fade.remaining_frames -= 1;
+            self.gain += fade.delta;
+            return true;
+        }
+
+        self.gain = fade.target;
+        let stop = fade.stop;
+        self.fade = None;
+        !stop
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fade {
+    delta: f32,
+    target: f32,
+    remaining_frames: u64,
+    stop: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SampleFormat;
+
+    fn spec() -> AudioSpec {
+        AudioSpec {
+            sample_format: SampleFormat::F32Le,
+            sample_rate: 100,
+            data_samples: 10,
+            channels: 2,
+        }
+    }
+
+    fn samples(data: &AudioData) -> Vec<f32> {
+        data.samples().map(|s| s.to_f32()).collect()
+    }
+
+    #[test]
+    fn mix_works() {
+        let mut mixer = Mixer::new(spec());
+        mixer.set_soft_clip(false);
+        let sfx = mixer.add_bus(0.5);
+        mixer.play(BusId::DEFAULT, std::iter::repeat_n(Sample::F32(0.25), 3));
+        mixer.play(sfx, std::iter::repeat(Sample::F32(0.5)));
+
+        let mut data = AudioData::new(spec());
+        mixer.mix(&mut data);
+        let samples = samples(&data);
+        assert_eq!(samples.len(), 20);
+        assert_eq!(samples[0], 0.5);
+        assert_eq!(samples[1], 0.5);
+        assert_eq!(samples[6], 0.25);
+        assert_eq!(mixer.voice_count(), 1);
+    }
+
+    #[test]
+    fn fade_out_stops_voice() {
+        let mut mixer = Mixer::new(spec());
+        let voice = mixer.play(BusId::DEFAULT, std::iter::repeat(Sample::F32(0.5)));
+        mixer.fade_out(voice, Duration::from_millis(50));
+
+        let mut data = AudioData::new(spec());
+        mixer.mix(&mut data);
+        assert!(!mixer.is_playing(voice));
+        assert_eq!(samples(&data)[19], 0.0);
+    }
+}
@@ -0,0 +1,158 @@
+use super::{AudioData, AudioSpec};
+use crate::event::{Event, TimeoutTag};
+use crate::System;
+use std::time::Duration;
+
+// Keeps the host audio queue fed by enqueueing fixed-size blocks ahead of `System::clock_game_time()`.
+#[derive(Debug)]
+pub struct AudioPump {
+    tag: TimeoutTag,
+    lead_time: Duration,
+    data: AudioData,
+    start_time: Option<Duration>,
+    enqueued_frames: u64,
+}
+
+impl AudioPump {
+    pub const DEFAULT_LEAD_TIME: Duration = Duration::from_millis(60);
+
+    pub fn new(tag: TimeoutTag) -> Self {
+        Self {
+            tag,
+            lead_time: Self::DEFAULT_LEAD_TIME,
+            data: AudioData::default(),
+            start_time: None,
+            enqueued_frames: 0,
+        }
+    }
+
+    pub fn tag(&self) -> TimeoutTag {
+        self.tag
+    }
+
+    pub fn spec(&self) -> AudioSpec {
+        self.data.spec()
+    }
+
+    pub fn lead_time(&self) -> Duration {
+        self.lead_time
+    }
+
+    pub fn set_lead_time(&mut self, lead_time: Duration) {
+        self.lead_time = lead_time;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    // Initializes the host audio and starts pumping.
+    pub fn start<S: System>(
+        &mut self,
+        system: &mut S,
+        sample_rate: u16,
+        channels: u8,
+        data_samples: usize,
+    ) -> AudioSpec {
+        let spec = system.audio_init(sample_rate, channels, data_samples);
+        self.data = AudioData::new(spec);
+        self.start_time = Some(system.clock_game_time());
+        self.enqueued_frames = 0;
+        system.clock_set_timeout(self.tag, Duration::ZERO);
+        spec
+    }
+
+    pub fn stop<S: System>(&mut self, system: &mut S) {
+        self.start_time = None;
+        system.clock_cancel_timeout(self.tag);
+    }
+
+    // Returns `true` if `event` was the timeout event of this pump.
+    //
+    // `fill` is called for each block and should overwrite all frames of the given buffer.
+    // If it returns `false`, the block is treated as silence and is not enqueued.
+    pub fn handle_event<S, F>(&mut self, system: &mut S, event: &Event, mut fill: F) -> bool
+    where
+        S: System,
+        F: FnMut(&mut AudioData) -> bool,
+    {
+        if *event != Event::Timeout(self.tag) {
+            return false;
+        }
+        let Some(start_time) = self.start_time else {
+            return true;
+        };
+        let spec = self.data.spec();
+        if spec.sample_rate == 0 || spec.data_samples == 0 {
+            return true;
+        }
+
+        let now = system.clock_game_time().saturating_sub(start_time);
+        if self.enqueued_time() < now {
+            // Underrun (e.g., the game was suspended): skip the lost time instead of catching up.
+            self.enqueued_frames =
+                (now.as_nanos() * u128::from(spec.sample_rate) / 1_000_000_000) as u64;
+        }
+
+        while self.enqueued_time() <= now + self.lead_time {
+            if fill(&mut self.data) {
+                system.audio_enqueue(self.data.as_ref());
+            }
+            self.enqueued_frames += spec.data_samples as u64;
+        }
+
+        let wait = self
+            .enqueued_time()
+            .saturating_sub(self.lead_time)
+            .saturating_sub(now);
+        system.clock_set_timeout(self.tag, wait);
+        true
+    }
+
+    fn enqueued_time(&self) -> Duration {
+        Duration::from_secs(self.enqueued_frames) / u32::from(self.data.spec().sample_rate)
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessSystem;
+
+    #[test]
+    fn lead_time_is_kept() {
+        let mut system = HeadlessSystem::new();
+        let mut pump = AudioPump::new(TimeoutTag::new(0));
+        pump.set_lead_time(Duration::from_millis(50));
+        pump.start(&mut system, 1000, 1, 20);
+
+        let mut blocks = 0;
+        for _ in 0..10 {
+            let event = system.advance_to_next_timeout().expect("timeout");
+            assert!(pump.handle_event(&mut system, &event, |_| {
+                blocks += 1;
+                true
+            }));
+
+            let now = system.clock_game_time();
+            assert!(pump.enqueued_time() > now + pump.lead_time());
+            assert!(pump.enqueued_time() <= now + pump.lead_time() + Duration::from_millis(20));
+        }
+        assert_eq!(system.take_audio_data().len(), blocks);
+    }
+
+    #[test]
+    fn long_underrun_works() {
+        let mut system = HeadlessSystem::new();
+        let mut pump = AudioPump::new(TimeoutTag::new(0));
+        pump.start(&mut system, 48000, 1, 480);
+
+        system.advance_clock(Duration::from_secs(5 * 24 * 60 * 60));
+        let event = system.advance_to_next_timeout().expect("timeout");
+        assert!(pump.handle_event(&mut system, &event, |_| true));
+
+        let now = system.clock_game_time();
+        assert!(pump.enqueued_time() > now + pump.lead_time());
+        assert!(pump.enqueued_time() <= now + pump.lead_time() + Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,169 @@
+use super::{AudioData, AudioSpec, WavWriter};
+use crate::event::TimeoutTag;
+use crate::spatial::{Region, Size};
+use crate::video::{VideoFrame, VideoFrameSpec};
+use crate::{Result, System, SystemCapabilities};
+use orfail::{Failure, OrFail};
+use std::io::{Seek, Write};
+use std::time::Duration;
+
+// Passes everything through to `inner` and writes the enqueued audio data into a WAV stream.
+//
+// The WAV stream uses the `AudioSpec` returned by the first `System::audio_init()` call.
+#[derive(Debug)]
+pub struct AudioRecorder<S, W: Write + Seek> {
+    inner: S,
+    writer: Option<W>,
+    wav: Option<WavWriter<W>>,
+    failed: Option<Failure>,
+}
+
+impl<S: System, W: Write + Seek> AudioRecorder<S, W> {
+    pub fn new(inner: S, writer: W) -> Self {
+        Self {
+            inner,
+            writer: Some(writer),
+            wav: None,
+            failed: None,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    // Returns the first error that occurred while writing the WAV stream.
+    pub fn take_error(&mut self) -> Option<Failure> {
+        self.failed.take()
+    }
+
+    pub fn finish(mut self) -> Result<(S, W)> {
+        if let Some(e) = self.failed.take() {
+            return Err(e);
+        }
+        let writer = match self.wav.take() {
+            Some(wav) => wav.finish().or_fail()?,
+            None => self.writer.take().or_fail()?,
+        };
+        Ok((self.inner, writer))
+    }
+}
+
+impl<S: System, W: Write + Seek> System for AudioRecorder<S, W> {
+    fn video_init(&mut self, resolution: Size) -> VideoFrameSpec {
+        self.inner.video_init(resolution)
+    }
+
+    fn video_draw(&mut self, frame: VideoFrame<&[u8]>) {
+        self.inner.video_draw(frame);
+    }
+
+    fn video_draw_regions(&mut self, frame: VideoFrame<&[u8]>, regions: &[Region]) {
+        self.inner.video_draw_regions(frame, regions);
+    }
+
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec {
+        let spec = self.inner.audio_init(sample_rate, channels, data_samples);
+        if self.failed.is_some() {
+            return spec;
+        }
+
+        if let Some(wav) = &self.wav {
+            let current = wav.spec();
+            if (current.sample_format, current.sample_rate, current.channels)
+                != (spec.sample_format, spec.sample_rate, spec.channels)
+            {
+                self.failed = Some(Failure::new(format!(
+                    "cannot change the audio spec of a WAV stream: {current:?} -> {spec:?}"
+                )));
+            }
+        } else if let Some(writer) = self.writer.take() {
+            match WavWriter::new(writer, spec).or_fail() {
+                Ok(wav) => self.wav = Some(wav),
+                Err(e) => self.failed = Some(e),
+            }
+        }
+        spec
+    }
+
+    fn audio_enqueue(&mut self, data: AudioData<&[u8]>) {
+        if self.failed.is_none() {
+            if let Some(wav) = &mut self.wav {
+                self.failed = wav.write(&data).or_fail().err();
+            }
+        }
+        self.inner.audio_enqueue(data);
+    }
+
+    fn clock_game_time(&self) -> Duration {
+        self.inner.clock_game_time()
+    }
+
+    fn clock_unix_time(&self) -> Duration {
+        self.inner.clock_unix_time()
+    }
+
+    fn clock_set_timeout(&mut self, tag: TimeoutTag, timeout: Duration) {
+        self.inner.clock_set_timeout(tag, timeout);
+    }
+
+    fn clock_cancel_timeout(&mut self, tag: TimeoutTag) {
+        self.inner.clock_cancel_timeout(tag);
+    }
+
+    fn storage_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.inner.storage_get(key).or_fail()
+    }
+
+    fn storage_put(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        self.inner.storage_put(key, value).or_fail()
+    }
+
+    fn storage_delete(&mut self, key: &str) -> Result<()> {
+        self.inner.storage_delete(key).or_fail()
+    }
+
+    fn text_input_set_enabled(&mut self, enabled: bool) {
+        self.inner.text_input_set_enabled(enabled);
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        self.inner.capabilities()
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+    use crate::audio::Sound;
+    use crate::headless::HeadlessSystem;
+    use std::io::Cursor;
+
+    #[test]
+    fn audio_recorder_works() -> Result<()> {
+        let mut system = AudioRecorder::new(HeadlessSystem::new(), Cursor::new(Vec::new()));
+        let spec = system.audio_init(8000, 2, 2);
+
+        let mut data = AudioData::new(spec);
+        data.write_frame(0, &[0.5f32, -0.5]);
+        data.write_frame(1, &[1.0f32]);
+        system.audio_enqueue(data.as_ref());
+        system.audio_enqueue(data.as_ref());
+
+        let (mut inner, writer) = system.finish().or_fail()?;
+        assert_eq!(inner.take_audio_data().len(), 2);
+
+        let sound = Sound::from_wav_bytes(writer.get_ref()).or_fail()?;
+        assert_eq!(sound.spec().sample_rate, 8000);
+        assert_eq!(sound.spec().channels, 2);
+        assert_eq!(sound.spec().data_samples, 4);
+        let expected = data.samples().chain(data.samples()).map(|x| x.to_i16());
+        assert!(sound.samples().map(|x| x.to_i16()).eq(expected));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use super::Sample;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+// Number of zero crossings on each side of the windowed-sinc kernel
+const SINC_ZERO_CROSSINGS: usize = 16;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum ResampleQuality {
+    // Cheap, but produces audible aliasing for high-frequency content
+    #[default]
+    Linear,
+
+    // Band-limited interpolation with a Blackman-windowed sinc kernel
+    Sinc,
+}
+
+// Converts the sample rate of a mono source.
+//
+// As this works as a stream, there are no glitches at the boundaries of `AudioData` blocks.
+// Output samples are always `Sample::F32`.
+#[derive(Debug)]
+pub struct Resample<I> {
+    source: I,
+    source_len: Option<u64>,
+    from_rate: u64,
+    to_rate: u64,
+    quality: ResampleQuality,
+    cutoff: f64,
+    half_width: u64,
+
+    // Source samples from the index `buf_start`
+    buf: VecDeque<f32>,
+    buf_start: u64,
+
+    // Current output position in the source is `pos + frac / to_rate`
+    pos: u64,
+    frac: u64,
+}
+
+impl<I: Iterator<Item = Sample>> Resample<I> {
+    pub fn new(source: I, from_rate: u16, to_rate: u16, quality: ResampleQuality) -> Self {
+        let from_rate = u64::from(from_rate.max(1));
+        let to_rate = u64::from(to_rate.max(1));
+
+        // Lowers the cutoff frequency when downsampling to avoid aliasing.
+        let cutoff = (to_rate as f64 / from_rate as f64).min(1.0);
+        let half_width = match quality {
+            ResampleQuality::Linear => 1,
+            ResampleQuality::Sinc => (SINC_ZERO_CROSSINGS as f64 / cutoff).ceil() as u64,
+        };
+        Self {
+            source,
+            source_len: None,
+            from_rate,
+            to_rate,
+            quality,
+            cutoff,
+            half_width,
+            buf: VecDeque::new(),
+            buf_start: 0,
+            pos: 0,
+            frac: 0,
+        }
+    }
+
+    fn fill(&mut self, until: u64) {
+        while self.source_len.is_none() && self.buf_start + self.buf.len() as u64 <= until {
+            match self.source.next() {
+                Some(x) => self.buf.push_back(x.to_f32()),
+                None => self.source_len = Some(self.buf_start + self.buf.len() as u64),
+            }
+        }
+    }
+
+    fn get(&self, i: i64) -> f32 {
+        if i < self.buf_start as i64 {
+            return 0.0;
+        }
+        self.buf
+            .get((i - self.buf_start as i64) as usize)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn interpolate(&self) -> f32 {
+        let pos = self.pos as i64;
+        let t = self.frac as f64 / self.to_rate as f64;
+        match self.quality {
+            ResampleQuality::Linear => {
+                let t = t as f32;
+                self.get(pos) * (1.0 - t) + self.get(pos + 1) * t
+            }
+            ResampleQuality::Sinc => {
+                let half_width = self.half_width as i64;
+                let mut sum = 0.0;
+                for i in pos - half_width + 1..=pos + half_width {
+                    let d = (pos - i) as f64 + t;
+                    sum += f64::from(self.get(i)) * self.kernel(d);
+                }
+                sum as f32
+            }
+        }
+    }
+
+    fn kernel(&self, d: f64) -> f64 {
+        let x = d / self.half_width as f64;
+        if x.abs() >= 1.0 {
+            return 0.0;
+        }
+        let window = 0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos();
+        let y = PI * self.cutoff * d;
+        let sinc = if y == 0.0 { 1.0 } else { y.sin() / y };
+        self.cutoff * sinc * window
+    }
+}
+
+impl<I: Iterator<Item = Sample>> Iterator for Resample<I> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill(self.pos + self.half_width);
+        if self.source_len.is_some_and(|n| self.pos >= n) {
+            return None;
+        }
+
+        let sample = self.interpolate();
+
+        self.frac += self.from_rate;
+        self.pos += self.frac / self.to_rate;
+        self.frac %= self.to_rate;
+        while self.buf_start + self.half_width < self.pos && !self.buf.is_empty() {
+            self.buf.pop_front();
+            self.buf_start += 1;
+        }
+
+        Some(Sample::F32(sample))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(rate: u16, hz: f64, n: usize) -> impl Iterator<Item = Sample> {
+        (0..n).map(move |i| Sample::F32((2.0 * PI * hz * i as f64 / f64::from(rate)).sin() as f32))
+    }
+
+    #[test]
+    fn resample_works() {
+        for quality in [ResampleQuality::Linear, ResampleQuality::Sinc] {
+            let output = Resample::new(sine(44100, 440.0, 44100), 44100, 48000, quality)
+                .map(|x| x.to_f32())
+                .collect::<Vec<_>>();
+            assert_eq!(output.len(), 48000);
+
+            // Skips the edges where the kernel overlaps the implicit silence.
+            let expected = sine(48000, 440.0, 48000).map(|x| x.to_f32());
+            for (x, y) in output.iter().zip(expected).skip(100).take(47800) {
+                assert!((x - y).abs() < 0.01, "{quality:?}: {x} vs {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn downsample_works() {
+        let output = Resample::new(sine(48000, 440.0, 4800), 48000, 8000, ResampleQuality::Sinc)
+            .collect::<Vec<_>>();
+        assert_eq!(output.len(), 800);
+    }
+}
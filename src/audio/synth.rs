@@ -0,0 +1,415 @@
+// Procedural sound effect synthesizer (inspired by sfxr).
+use super::Sample;
+use std::f32::consts::PI;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum Waveform {
+    #[default]
+    Square,
+    Triangle,
+    Saw,
+    Sine,
+    Noise,
+}
+
+#[derive(Debug, Clone)]
+pub struct Oscillator {
+    waveform: Waveform,
+    duty: f32,
+    phase: f32,
+    noise: f32,
+    rng: Rng,
+}
+
+impl Oscillator {
+    pub fn new(waveform: Waveform) -> Self {
+        Self::with_seed(waveform, 0)
+    }
+
+    // `seed` is only used by `Waveform::Noise`.
+    pub fn with_seed(waveform: Waveform, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        Self {
+            waveform,
+            duty: 0.5,
+            phase: 0.0,
+            noise: rng.range(-1.0, 1.0),
+            rng,
+        }
+    }
+
+    pub fn waveform(&self) -> Waveform {
+        self.waveform
+    }
+
+    // Ratio of the high part of `Waveform::Square` (clamped to `0.0..=1.0`).
+    pub fn set_duty(&mut self, duty: f32) {
+        self.duty = duty.clamp(0.0, 1.0);
+    }
+
+    // Returns the next value in `-1.0..=1.0`.
+    pub fn next_value(&mut self, frequency: f32, sample_rate: u16) -> f32 {
+        let value = match self.waveform {
+            Waveform::Square if self.phase < self.duty => 1.0,
+            Waveform::Square => -1.0,
+            Waveform::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            Waveform::Saw => 2.0 * self.phase - 1.0,
+            Waveform::Sine => (2.0 * PI * self.phase).sin(),
+            Waveform::Noise => self.noise,
+        };
+
+        let prev_phase = self.phase;
+        self.phase = (self.phase + frequency.max(0.0) / f32::from(sample_rate.max(1))).fract();
+        if (prev_phase < 0.5) != (self.phase < 0.5) || self.phase < prev_phase {
+            // Changes the noise value twice per period.
+            self.noise = self.rng.range(-1.0, 1.0);
+        }
+        value
+    }
+}
+
+// Attack-decay-sustain-release envelope (durations are in seconds).
+//
+// As sound effects have no note-off, the sustain stage also has a fixed duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct Adsr {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub sustain_level: f32,
+    pub release: f32,
+}
+
+impl Adsr {
+    pub fn duration(&self) -> f32 {
+        self.attack + self.decay + self.sustain + self.release
+    }
+
+    // Returns `None` if the envelope has finished.
+    pub fn level(&self, t: f32) -> Option<f32> {
+        let mut t = t;
+        if t < self.attack {
+            return Some(t / self.attack);
+        }
+        t -= self.attack;
+        if t < self.decay {
+            return Some(1.0 - (1.0 - self.sustain_level) * t / self.decay);
+        }
+        t -= self.decay;
+        if t < self.sustain {
+            return Some(self.sustain_level);
+        }
+        t -= self.sustain;
+        if t < self.release {
+            return Some(self.sustain_level * (1.0 - t / self.release));
+        }
+        None
+    }
+}
+
+impl Default for Adsr {
+    fn default() -> Self {
+        Self {
+            attack: 0.0,
+            decay: 0.05,
+            sustain: 0.1,
+            sustain_level: 0.5,
+            release: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum SfxPreset {
+    Pickup,
+    Laser,
+    Explosion,
+    PowerUp,
+    Hit,
+    Jump,
+    Blip,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SfxParams {
+    pub waveform: Waveform,
+    pub duty: f32,
+    pub volume: f32,
+    pub envelope: Adsr,
+
+    // Start frequency in Hz
+    pub frequency: f32,
+
+    // The sound stops when the frequency slides below this value
+    pub min_frequency: f32,
+
+    // Pitch slide in octaves per second
+    pub slide: f32,
+
+    // Change of `slide` in octaves per second squared
+    pub delta_slide: f32,
+
+    // Vibrato depth in octaves
+    pub vibrato_depth: f32,
+    pub vibrato_frequency: f32,
+
+    // Seed of `Waveform::Noise`
+    pub seed: u64,
+}
+
+impl SfxParams {
+    // Generates randomized parameters of the given kind (the same `seed` yields the same parameters).
+    pub fn from_preset(preset: SfxPreset, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut params = Self {
+            seed: rng.next_u64(),
+            ..Self::default()
+        };
+        match preset {
+            SfxPreset::Pickup => {
+                params.frequency = rng.range(800.0, 1600.0);
+                params.envelope = envelope(0.0, rng.range(0.05, 0.1), rng.range(0.05, 0.15));
+                if rng.chance(0.5) {
+                    params.slide = rng.range(1.0, 4.0);
+                }
+            }
+            SfxPreset::Laser => {
+                params.waveform = *rng.choose(&[Waveform::Square, Waveform::Saw, Waveform::Sine]);
+                params.duty = rng.range(0.2, 0.5);
+                params.frequency = rng.range(800.0, 2400.0);
+                params.min_frequency = params.frequency * rng.range(0.1, 0.3);
+                params.slide = -rng.range(4.0, 12.0);
+                params.envelope = envelope(0.0, rng.range(0.05, 0.15), rng.range(0.05, 0.2));
+            }
+            SfxPreset::Explosion => {
+                params.waveform = Waveform::Noise;
+                params.frequency = rng.range(200.0, 1600.0);
+                params.slide = -rng.range(0.5, 2.0);
+                params.envelope = envelope(0.0, rng.range(0.1, 0.3), rng.range(0.2, 0.6));
+            }
+            SfxPreset::PowerUp => {
+                params.waveform = *rng.choose(&[Waveform::Square, Waveform::Saw]);
+                params.frequency = rng.range(200.0, 600.0);
+                params.slide = rng.range(1.0, 3.0);
+                params.vibrato_depth = rng.range(0.0, 0.1);
+                params.vibrato_frequency = rng.range(8.0, 16.0);
+                params.envelope = envelope(0.0, rng.range(0.2, 0.4), rng.range(0.1, 0.3));
+            }
+            SfxPreset::Hit => {
+                params.waveform = *rng.choose(&[Waveform::Square, Waveform::Saw, Waveform::Noise]);
+                params.frequency = rng.range(200.0, 800.0);
+                params.slide = -rng.range(2.0, 6.0);
+                params.envelope = envelope(0.0, rng.range(0.02, 0.05), rng.range(0.05, 0.15));
+            }
+            SfxPreset::Jump => {
+                params.duty = rng.range(0.2, 0.5);
+                params.frequency = rng.range(300.0, 700.0);
+                params.slide = rng.range(2.0, 5.0);
+                params.envelope = envelope(0.0, rng.range(0.1, 0.2), rng.range(0.05, 0.15));
+            }
+            SfxPreset::Blip => {
+                params.waveform = *rng.choose(&[Waveform::Square, Waveform::Sine]);
+                params.duty = rng.range(0.2, 0.5);
+                params.frequency = rng.range(400.0, 1200.0);
+                params.envelope = envelope(0.0, rng.range(0.05, 0.1), 0.02);
+            }
+        }
+        params
+    }
+
+    pub fn play(&self, sample_rate: u16) -> Sfx {
+        Sfx::new(self.clone(), sample_rate)
+    }
+}
+
+impl Default for SfxParams {
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::Square,
+            duty: 0.5,
+            volume: 0.5,
+            envelope: Adsr::default(),
+            frequency: 440.0,
+            min_frequency: 20.0,
+            slide: 0.0,
+            delta_slide: 0.0,
+            vibrato_depth: 0.0,
+            vibrato_frequency: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+fn envelope(attack: f32, sustain: f32, release: f32) -> Adsr {
+    Adsr {
+        attack,
+        decay: 0.0,
+        sustain,
+        sustain_level: 1.0,
+        release,
+    }
+}
+
+// Iterator over the samples of a sound effect.
+#[derive(Debug, Clone)]
+pub struct Sfx {
+    params: SfxParams,
+    sample_rate: u16,
+    oscillator: Oscillator,
+    frame: u64,
+    frequency: f32,
+    slide: f32,
+}
+
+impl Sfx {
+    pub fn new(params: SfxParams, sample_rate: u16) -> Self {
+        let mut oscillator = Oscillator::with_seed(params.waveform, params.seed);
+        oscillator.set_duty(params.duty);
+        Self {
+            frequency: params.frequency,
+            slide: params.slide,
+            params,
+            sample_rate: sample_rate.max(1),
+            oscillator,
+            frame: 0,
+        }
+    }
+
+    pub fn params(&self) -> &SfxParams {
+        &self.params
+    }
+}
+
+impl Iterator for Sfx {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample_rate = f32::from(self.sample_rate);
+        let t = self.frame as f32 / sample_rate;
+        let level = self.params.envelope.level(t)?;
+        if self.frequency < self.params.min_frequency {
+            return None;
+        }
+
+        let vibrato = (2.0 * PI * self.params.vibrato_frequency * t).sin();
+        let frequency = self.frequency * (self.params.vibrato_depth * vibrato).exp2();
+        let value = self.oscillator.next_value(frequency, self.sample_rate);
+
+        self.frame += 1;
+        self.frequency *= (self.slide / sample_rate).exp2();
+        self.slide += self.params.delta_slide / sample_rate;
+
+        Some(Sample::F32(value * level * self.params.volume))
+    }
+}
+
+// xorshift64*
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Scrambles the seed with SplitMix64 (xorshift never leaves the all-zero state).
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        let x = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        min + (max - min) * x
+    }
+
+    fn chance(&mut self, p: f32) -> bool {
+        self.range(0.0, 1.0) < p
+    }
+
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[(self.next_u64() % items.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adsr_works() {
+        let adsr = Adsr {
+            attack: 1.0,
+            decay: 1.0,
+            sustain: 1.0,
+            sustain_level: 0.5,
+            release: 1.0,
+        };
+        assert_eq!(adsr.level(0.5), Some(0.5));
+        assert_eq!(adsr.level(1.5), Some(0.75));
+        assert_eq!(adsr.level(2.5), Some(0.5));
+        assert_eq!(adsr.level(3.5), Some(0.25));
+        assert_eq!(adsr.level(4.0), None);
+    }
+
+    #[test]
+    fn sfx_works() {
+        for preset in [
+            SfxPreset::Pickup,
+            SfxPreset::Laser,
+            SfxPreset::Explosion,
+            SfxPreset::PowerUp,
+            SfxPreset::Hit,
+            SfxPreset::Jump,
+            SfxPreset::Blip,
+        ] {
+            let params = SfxParams::from_preset(preset, 1);
+            assert_eq!(params, SfxParams::from_preset(preset, 1));
+
+            let samples = params.play(8000).map(|x| x.to_f32()).collect::<Vec<_>>();
+            assert!(!samples.is_empty(), "{preset:?}");
+            assert!(samples.len() as f32 <= params.envelope.duration() * 8000.0 + 1.0);
+            assert!(samples.iter().all(|x| x.abs() <= 1.0));
+            assert!(samples.iter().any(|&x| x != 0.0));
+        }
+    }
+
+    #[test]
+    fn rng_works() {
+        for seed in [
+            0,
+            0x9E37_79B9_7F4A_7C15,
+            0x9E37_79B9_7F4A_7C15u64.wrapping_neg(),
+        ] {
+            let mut rng = Rng::new(seed);
+            let values = (0..4).map(|_| rng.next_u64()).collect::<Vec<_>>();
+            assert!(values.iter().all(|&x| x != 0), "{seed}");
+            assert_ne!(values[0], values[1], "{seed}");
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use super::{AudioData, AudioSpec, Frames, SampleFormat, Samples};
+use crate::Result;
+use orfail::{Failure, OrFail};
+use std::io::{Seek, SeekFrom, Write};
+use std::time::Duration;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+// Size of the RIFF, "fmt " and "data" headers written by `WavWriter`
+const HEADER_SIZE: u32 = 46;
+
+// Decoded sound asset.
+//
+// 8-bit and 16-bit PCM are stored as `SampleFormat::I16Le`, and the others are stored as `SampleFormat::F32Le`.
+#[derive(Debug)]
+pub struct Sound {
+    data: AudioData,
+}
+
+impl Sound {
+    // Decodes RIFF/WAV bytes (PCM 8/16/24/32-bit or IEEE float 32-bit).
+    pub fn from_wav_bytes(bytes: &[u8]) -> Result<Self> {
+        (bytes.len() >= 12).or_fail_with(|()| "too short WAV data".to_owned())?;
+        (&bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE")
+            .or_fail_with(|()| "not a RIFF/WAV data".to_owned())?;
+
+        let mut fmt = None;
+        let mut data = None;
+        let mut chunks = &bytes[12..];
+        while chunks.len() >= 8 {
+            let id = &chunks[0..4];
+            let size = read_u32(&chunks[4..8]) as usize;
+            // `size` comes from the file, so `8 + size` may overflow on 32-bit targets.
+            let body = chunks[8..].get(..size).or_fail_with(|()| {
+                format!("truncated WAV chunk: {:?}", String::from_utf8_lossy(id))
+            })?;
+            match id {
+                b"fmt " => fmt = Some(Format::decode(body).or_fail()?),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+
+            // Chunks are padded to even sizes.
+            let next = (8 + body.len() + size % 2).min(chunks.len());
+            chunks = &chunks[next..];
+        }
+
+        let fmt = fmt.or_fail_with(|()| "missing WAV 'fmt ' chunk".to_owned())?;
+        let data = data.or_fail_with(|()| "missing WAV 'data' chunk".to_owned())?;
+        fmt.decode_samples(data).or_fail()
+    }
+
+    pub fn spec(&self) -> AudioSpec {
+        self.data.spec()
+    }
+
+    pub fn duration(&self) -> Duration {
+        let spec = self.spec();
+        Duration::from_secs(spec.data_samples as u64) / u32::from(spec.sample_rate.max(1))
+    }
+
+    pub fn samples(&self) -> Samples<'_> {
+        self.data.samples()
+    }
+
+    pub fn frames(&self) -> Frames<'_> {
+        self.data.frames()
+    }
+
+    pub fn audio_data(&self) -> AudioData<&[u8]> {
+        self.data.as_ref()
+    }
+
+    // Converts into the format negotiated by `System::audio_init()` (see also `AudioData::convert()`).
+    pub fn convert(&self, spec: AudioSpec) -> AudioData {
+        self.data.convert(spec)
+    }
+}
+
+#[derive(Debug)]
+struct Format {
+    format_tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+impl Format {
+    fn decode(body: &[u8]) -> Result<Self> {
+        (body.len() >= 16).or_fail_with(|()| "too short WAV 'fmt ' chunk".to_owned())?;
+        let mut format_tag = read_u16(&body[0..2]);
+        if format_tag == WAVE_FORMAT_EXTENSIBLE {
+            // The first two bytes of the sub-format GUID are the actual format tag.
+            (body.len() >= 26).or_fail_with(|()| "too short WAV 'fmt ' chunk".to_owned())?;
+            format_tag = read_u16(&body[24..26]);
+        }
+        Ok(Self {
+            format_tag,
+            channels: read_u16(&body[2..4]),
+            sample_rate: read_u32(&body[4..8]),
+            bits_per_sample: read_u16(&body[14..16]),
+        })
+    }
+
+    fn decode_samples(&self, data: &[u8]) -> Result<Sound> {
+        let channels = u8::try_from(self.channels)
+            .ok()
+            .filter(|&n| n > 0)
+            .or_fail_with(|()| format!("unsupported WAV channels: {}", self.channels))?;
+        let sample_rate = u16::try_from(self.sample_rate)
+            .or_fail_with(|_| format!("unsupported WAV sample rate: {}", self.sample_rate))?;
+        let (sample_format, decode): (_, fn(&[u8]) -> super::Sample) =
+            match (self.format_tag, self.bits_per_sample) {
+                (WAVE_FORMAT_PCM, 8) => (SampleFormat::I16Le, |b| {
+                    ((i16::from(b[0]) - 128) << 8).into()
+                }),
+                (WAVE_FORMAT_PCM, 16) => (SampleFormat::I16Le, |b| read_i16(b).into()),
+                (WAVE_FORMAT_PCM, 24) => (SampleFormat::F32Le, |b| {
+                    let v = i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8;
+                    (v as f32 / 8_388_608.0).into()
+                }),
+                (WAVE_FORMAT_PCM, 32) => (SampleFormat::F32Le, |b| {
+                    let v = i32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                    (v as f32 / 2_147_483_648.0).into()
+                }),
+                (WAVE_FORMAT_IEEE_FLOAT, 32) => (SampleFormat::F32Le, |b| {
+                    f32::from_le_bytes([b[0], b[1], b[2], b[3]]).into()
+                }),
+                (tag, bits) => {
+                    return Err(Failure::new(format!(
+                        "unsupported WAV format: tag={tag:#06x}, bits_per_sample={bits}"
+                    )));
+                }
+            };
+
+        let sample_bytes = usize::from(self.bits_per_sample / 8);
+        let frame_bytes = sample_bytes * usize::from(channels);
+        let spec = AudioSpec {
+            sample_format,
+            sample_rate,
+            data_samples: data.len() / frame_bytes,
+            channels,
+        };
+        let mut audio_data = AudioData::new(spec);
+        for (i, b) in data[..spec.data_samples * frame_bytes]
+            .chunks_exact(sample_bytes)
+            .enumerate()
+        {
+            audio_data.write_sample(i, decode(b));
+        }
+        Ok(Sound { data: audio_data })
+    }
+}
+
+// Writes `AudioData` blocks into a RIFF/WAV stream.
+//
+// The sizes in the header are fixed up by `finish()` (or on drop, ignoring errors).
+#[derive(Debug)]
+pub struct WavWriter<W: Write + Seek> {
+    writer: Option<W>,
+    start: u64,
+    spec: AudioSpec,
+    data_bytes: u32,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    // `spec.data_samples` is ignored, so blocks of any length can be written.
+    pub fn new(mut writer: W, spec: AudioSpec) -> Result<Self> {
+        let (format_tag, bits_per_sample) = match spec.sample_format {
+            SampleFormat::I16Be | SampleFormat::I16Le => (WAVE_FORMAT_PCM, 16),
+            SampleFormat::F32Be | SampleFormat::F32Le => (WAVE_FORMAT_IEEE_FLOAT, 32),
+        };
+        let channels = u16::from(spec.channels.max(1));
+        let block_align = channels * bits_per_sample / 8;
+        let byte_rate = u32::from(spec.sample_rate) * u32::from(block_align);
+
+        let start = writer.stream_position().or_fail()?;
+        let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+        header.extend_from_slice(b"RIFF\0\0\0\0WAVE");
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&18u32.to_le_bytes());
+        header.extend_from_slice(&format_tag.to_le_bytes());
+        header.extend_from_slice(&channels.to_le_bytes());
+        header.extend_from_slice(&u32::from(spec.sample_rate).to_le_bytes());
+        header.extend_from_slice(&byte_rate.to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&bits_per_sample.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // cbSize
+        header.extend_from_slice(b"data\0\0\0\0");
+        writer.write_all(&header).or_fail()?;
+
+        Ok(Self {
+            writer: Some(writer),
+            start,
+            spec,
+            data_bytes: 0,
+        })
+    }
+
+    pub fn spec(&self) -> AudioSpec {
+        self.spec
+    }
+
+    pub fn write<B: AsRef<[u8]>>(&mut self, data: &AudioData<B>) -> Result<()> {
+        let spec = data.spec();
+        (spec.sample_format == self.spec.sample_format
+            && spec.sample_rate == self.spec.sample_rate
+            && spec.channels == self.spec.channels)
+            .or_fail_with(|()| format!("audio spec mismatch: {:?} != {:?}", spec, self.spec))?;
+        let n = u32::try_from(data.bytes().len())
+            .ok()
+            .and_then(|n| n.checked_add(self.data_bytes))
+            .filter(|&n| n <= u32::MAX - HEADER_SIZE)
+            .or_fail_with(|()| "too large WAV data".to_owned())?;
+
+        let writer = self.writer.as_mut().or_fail()?;
+        match spec.sample_format {
+            SampleFormat::I16Le | SampleFormat::F32Le => {
+                writer.write_all(data.bytes()).or_fail()?
+            }
+            SampleFormat::I16Be => {
+                for b in data.bytes().chunks_exact(2) {
+                    writer.write_all(&[b[1], b[0]]).or_fail()?;
+                }
+            }
+            SampleFormat::F32Be => {
+                for b in data.bytes().chunks_exact(4) {
+                    writer.write_all(&[b[3], b[2], b[1], b[0]]).or_fail()?;
+                }
+            }
+        }
+        self.data_bytes = n;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        self.fix_header().or_fail()?;
+        self.writer.take().or_fail()
+    }
+
+    fn fix_header(&mut self) -> Result<()> {
+        let writer = self.writer.as_mut().or_fail()?;
+        writer.seek(SeekFrom::Start(self.start + 4)).or_fail()?;
+        writer
+            .write_all(&(HEADER_SIZE - 8 + self.data_bytes).to_le_bytes())
+            .or_fail()?;
+        writer
+            .seek(SeekFrom::Start(self.start + u64::from(HEADER_SIZE) - 4))
+            .or_fail()?;
+        writer.write_all(&self.data_bytes.to_le_bytes()).or_fail()?;
+        let end = self.start + u64::from(HEADER_SIZE) + u64::from(self.data_bytes);
+        writer.seek(SeekFrom::Start(end)).or_fail()?;
+        writer.flush().or_fail()?;
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Drop for WavWriter<W> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            let _ = self.fix_header();
+        }
+    }
+}
+
+fn read_u16(b: &[u8]) -> u16 {
+    u16::from_le_bytes([b[0], b[1]])
+}
+
+fn read_i16(b: &[u8]) -> i16 {
+    i16::from_le_bytes([b[0], b[1]])
+}
+
+fn read_u32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(format_tag: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&format_tag.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&8000u32.to_le_bytes());
+        fmt.extend_from_slice(&(8000 * u32::from(block_align)).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        for (id, body) in [(b"LIST", &b"abc"[..]), (b"fmt ", &fmt), (b"data", data)] {
+            bytes.extend_from_slice(id);
+            bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(body);
+            if body.len() % 2 == 1 {
+                bytes.push(0);
+            }
+        }
+        let riff_size = (bytes.len() - 8) as u32;
+        bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+        bytes
+    }
+
+    fn samples(sound: &Sound) -> Vec<f32> {
+        sound.samples().map(|x| x.to_f32()).collect()
+    }
+
+    #[test]
+    fn decode_works() -> Result<()> {
+        let sound = Sound::from_wav_bytes(&wav_bytes(1, 1, 8, &[128, 255, 0])).or_fail()?;
+        assert_eq!(sound.spec().sample_rate, 8000);
+        assert_eq!(sound.spec().data_samples, 3);
+        assert_eq!(
+            sound.samples().map(|x| x.to_i16()).collect::<Vec<_>>(),
+            [0, 32512, -32768]
+        );
+
+        let data = [0, 0, 0xff, 0x7f, 0, 0x80, 0, 0];
+        let sound = Sound::from_wav_bytes(&wav_bytes(1, 2, 16, &data)).or_fail()?;
+        assert_eq!(sound.spec().channels, 2);
+        assert_eq!(samples(&sound), [0.0, 1.0, -1.0, 0.0]);
+
+        let data = [0, 0, 0x40, 0, 0, 0xc0];
+        let sound = Sound::from_wav_bytes(&wav_bytes(1, 1, 24, &data)).or_fail()?;
+        assert_eq!(samples(&sound), [0.5, -0.5]);
+
+        let data = [0.25f32.to_le_bytes(), (-1.0f32).to_le_bytes()].concat();
+        let sound = Sound::from_wav_bytes(&wav_bytes(3, 1, 32, &data)).or_fail()?;
+        assert_eq!(samples(&sound), [0.25, -1.0]);
+        assert_eq!(sound.duration(), Duration::from_micros(250));
+
+        assert!(Sound::from_wav_bytes(&wav_bytes(3, 1, 64, &[0; 8])).is_err());
+        assert!(Sound::from_wav_bytes(b"RIFF\0\0\0\0WAVE").is_err());
+        assert!(Sound::from_wav_bytes(b"RIFF\0\0\0\0WAVEdata\xf8\xff\xff\xff").is_err());
+
+        Ok(())
+    }
+}
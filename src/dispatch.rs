@@ -0,0 +1,221 @@
+#[cfg(feature = "image")]
+use crate::video::VideoFrame;
+use crate::{Result, System};
+use orfail::{Failure, OrFail};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+type QueryHandler<G, S> = Box<dyn Fn(&mut G, &mut S) -> Result<Vec<u8>>>;
+type CommandHandler<G, S> = Box<dyn Fn(&mut G, &mut S, &[u8]) -> Result<()>>;
+
+// Typed dispatcher for `Game::query()` and `Game::command()`.
+//
+// Query outputs and command inputs are encoded as JSON.
+pub struct Dispatcher<G, S> {
+    queries: BTreeMap<String, (String, QueryHandler<G, S>)>,
+    commands: BTreeMap<String, (String, CommandHandler<G, S>)>,
+}
+
+impl<G: 'static, S: System + 'static> Dispatcher<G, S> {
+    pub const LIST_QUERY_NAME: &'static str = "__list";
+
+    #[cfg(feature = "image")]
+    pub const SCREENSHOT_QUERY_NAME: &'static str = "screenshot";
+
+    pub fn new() -> Self {
+        Self {
+            queries: BTreeMap::new(),
+            commands: BTreeMap::new(),
+        }
+    }
+
+    // `schema` describes the output of the query (e.g., a JSON Schema) and is reported by the `"__list"` query.
+    pub fn register_query<T, F>(&mut self, name: &str, schema: &str, handler: F) -> &mut Self
+    where
+        T: Serialize,
+        F: 'static + Fn(&mut G, &mut S) -> Result<T>,
+    {
+        let handler = move |game: &mut G, system: &mut S| {
+            let output = handler(game, system).or_fail()?;
+            serde_json::to_vec(&output).or_fail()
+        };
+        self.queries
+            .insert(name.to_owned(), (schema.to_owned(), Box::new(handler)));
+        self
+    }
+
+    // `schema` describes the input of the command (e.g., a JSON Schema) and is reported by the `"__list"` query.
+    pub fn register_command<T, F>(&mut self, name: &str, schema: &str, handler: F) -> &mut Self
+    where
+        T: DeserializeOwned,
+        F: 'static + Fn(&mut G, &mut S, T) -> Result<()>,
+    {
+        let handler = move |game: &mut G, system: &mut S, data: &[u8]| {
+            let input = serde_json::from_slice(data).or_fail()?;
+            handler(game, system, input).or_fail()
+        };
+        self.commands
+            .insert(name.to_owned(), (schema.to_owned(), Box::new(handler)));
+        self
+    }
+
+    // Registers the `"screenshot"` query that returns the current frame as PNG bytes (not JSON).
+    #[cfg(feature = "image")]
+    pub fn register_screenshot_query(&mut self, frame: fn(&G) -> &VideoFrame) -> &mut Self {
+        let handler = move |game: &mut G, _: &mut S| Ok(frame(game).to_png());
+        self.queries.insert(
+            Self::SCREENSHOT_QUERY_NAME.to_owned(),
+            ("image/png".to_owned(), Box::new(handler)),
+        );
+        self
+    }
+
+    pub fn query(&self, game: &mut G, system: &mut S, name: &str) -> Result<Vec<u8>> {
+        if name == Self::LIST_QUERY_NAME {
+            return serde_json::to_vec(&self.list()).or_fail();
+        }
+        let (_, handler) = self
+            .queries
+            .get(name)
+            .ok_or_else(|| Failure::new(format!("unknown query: {name:?}")))?;
+        handler(game, system).or_fail()
+    }
+
+    pub fn command(&self, game: &mut G, system: &mut S, name: &str, data: &[u8]) -> Result<()> {
+        let (_, handler) = self
+            .commands
+            .get(name)
+            .ok_or_else(|| Failure::new(format!("unknown command: {name:?}")))?;
+        handler(game, system, data).or_fail()
+    }
+
+    pub fn list(&self) -> DispatcherList {
+        DispatcherList {
+            queries: self
+                .queries
+                .iter()
+                .map(|(name, (schema, _))| HandlerInfo {
+                    name: name.clone(),
+                    schema: schema.clone(),
+                })
+                .collect(),
+            commands: self
+                .commands
+                .iter()
+                .map(|(name, (schema, _))| HandlerInfo {
+                    name: name.clone(),
+                    schema: schema.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<G: 'static, S: System + 'static> Default for Dispatcher<G, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G, S> std::fmt::Debug for Dispatcher<G, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field("queries", &self.queries.keys().collect::<Vec<_>>())
+            .field("commands", &self.commands.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+// Output of the built-in `"__list"` query.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatcherList {
+    pub queries: Vec<HandlerInfo>,
+    pub commands: Vec<HandlerInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandlerInfo {
+    pub name: String,
+
+    // Schema given at registration (`"image/png"` for the screenshot query)
+    pub schema: String,
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessSystem;
+
+    const UINT_SCHEMA: &str = r#"{"type":"integer","minimum":0}"#;
+
+    #[derive(Debug, Default)]
+    struct Counter {
+        value: u32,
+    }
+
+    fn dispatcher() -> Dispatcher<Counter, HeadlessSystem> {
+        let mut dispatcher = Dispatcher::new();
+        dispatcher
+            .register_query("value", UINT_SCHEMA, |game: &mut Counter, _| Ok(game.value))
+            .register_command("add", UINT_SCHEMA, |game: &mut Counter, _, n: u32| {
+                game.value += n;
+                Ok(())
+            });
+        dispatcher
+    }
+
+    #[test]
+    fn dispatch_works() -> Result<()> {
+        let dispatcher = dispatcher();
+        let mut game = Counter::default();
+        let mut system = HeadlessSystem::new();
+
+        dispatcher
+            .command(&mut game, &mut system, "add", b"3")
+            .or_fail()?;
+        let value = dispatcher
+            .query(&mut game, &mut system, "value")
+            .or_fail()?;
+        assert_eq!(value, b"3");
+
+        assert!(dispatcher.query(&mut game, &mut system, "foo").is_err());
+        assert!(dispatcher
+            .command(&mut game, &mut system, "add", b"\"3\"")
+            .is_err());
+
+        let list = dispatcher
+            .query(&mut game, &mut system, "__list")
+            .or_fail()?;
+        let list: DispatcherList = serde_json::from_slice(&list).or_fail()?;
+        assert_eq!(list.queries[0].name, "value");
+        assert_eq!(list.commands[0].schema, UINT_SCHEMA);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn screenshot_query_works() -> Result<()> {
+        struct Screen {
+            frame: VideoFrame,
+        }
+
+        let mut dispatcher = Dispatcher::<Screen, HeadlessSystem>::new();
+        dispatcher.register_screenshot_query(|game| &game.frame);
+
+        let mut system = HeadlessSystem::new();
+        let spec = system.video_init(crate::spatial::Size::from_wh(4, 3));
+        let mut game = Screen {
+            frame: VideoFrame::new(spec),
+        };
+        let png = dispatcher
+            .query(&mut game, &mut system, "screenshot")
+            .or_fail()?;
+        assert_eq!(png, game.frame.to_png());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use crate::spatial::{Position, Size};
+use std::ops::BitOr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize),
@@ -8,16 +9,43 @@ use crate::spatial::{Position, Size};
 )]
 pub enum Event {
     Key(KeyEvent),
-    // TOOD: s/Mouse/Pointer/
-    Mouse(MouseEvent),
+    KeyUp(KeyEvent),
+    // Committed text (typed, pasted or confirmed by an IME)
+    TextInput(String),
+    Composition(CompositionEvent),
+    // The game window (or terminal) gained (`true`) or lost (`false`) the input focus
+    FocusChanged(bool),
+    // The game became invisible (e.g., a hidden browser tab) and should pause timers and audio
+    Suspended,
+    Resumed,
+    // The user or the host asked the game to quit (e.g., SIGTERM or closing the window).
+    // The game can save its state and return `Ok(false)` from `Game::handle_event()`, or ignore this event.
+    TerminateRequested,
+    Pointer(PointerEvent),
     Timeout(TimeoutTag),
     WindowResized(Size),
+    Gamepad(GamepadEvent),
+    // Positive deltas mean scrolling right / down (in pixels)
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    Wheel {
+        delta_x: i32,
+        delta_y: i32,
+        position: Position,
+    },
 }
 
 impl Event {
     pub fn position(&self) -> Option<Position> {
         match self {
-            Event::Mouse(event) => Some(event.position()),
+            Event::Pointer(event) => Some(event.position),
+            Event::Wheel { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    pub fn to_mouse_event(&self) -> Option<MouseEvent> {
+        match self {
+            Event::Pointer(event) => event.to_mouse_event(),
             _ => None,
         }
     }
@@ -32,6 +60,10 @@ impl Event {
 pub struct KeyEvent {
     pub ctrl: bool,
     pub alt: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shift: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub meta: bool,
     pub key: Key,
 }
 
@@ -40,11 +72,149 @@ impl From<Key> for KeyEvent {
         Self {
             ctrl: false,
             alt: false,
+            shift: false,
+            meta: false,
             key,
         }
     }
 }
 
+// Events of an in-progress IME composition (the final text is delivered by `Event::TextInput`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum CompositionEvent {
+    Start,
+    Update { text: String },
+    End { text: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct PointerEvent {
+    pub id: PointerId,
+    pub kind: PointerKind,
+    pub action: PointerAction,
+    pub position: Position,
+    pub buttons: PointerButtons,
+
+    // The button that changed state (only set for `Down` and `Up` actions)
+    pub button: Option<MouseButton>,
+
+    pub is_primary: bool,
+}
+
+impl PointerEvent {
+    // Converts the events of the primary pointer into the single-pointer `MouseEvent`
+    // for games that do not need multi-touch support.
+    pub fn to_mouse_event(&self) -> Option<MouseEvent> {
+        if !self.is_primary {
+            return None;
+        }
+
+        let position = self.position;
+        let button = self.button.unwrap_or_default();
+        match self.action {
+            PointerAction::Move => Some(MouseEvent::Move { position }),
+            PointerAction::Down => Some(MouseEvent::Down { position, button }),
+            PointerAction::Up | PointerAction::Cancel => Some(MouseEvent::Up { position, button }),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerId(u32);
+
+impl PointerId {
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum PointerKind {
+    #[default]
+    Mouse,
+    Touch,
+    Pen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum PointerAction {
+    Move,
+    Down,
+    Up,
+    Cancel,
+}
+
+// Uses the same bit layout as `MouseEvent.buttons` in DOM.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerButtons(u8);
+
+impl PointerButtons {
+    pub const NONE: Self = Self(0);
+    pub const PRIMARY: Self = Self(1);
+    pub const SECONDARY: Self = Self(2);
+    pub const AUXILIARY: Self = Self(4);
+
+    pub const fn new(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<MouseButton> for PointerButtons {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Self::PRIMARY,
+            MouseButton::Right => Self::SECONDARY,
+            MouseButton::Middle => Self::AUXILIARY,
+        }
+    }
+}
+
+impl BitOr for PointerButtons {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
@@ -52,9 +222,19 @@ impl From<Key> for KeyEvent {
     serde(rename_all = "camelCase")
 )]
 pub enum MouseEvent {
-    Move { position: Position },
-    Down { position: Position },
-    Up { position: Position },
+    Move {
+        position: Position,
+    },
+    Down {
+        position: Position,
+        #[cfg_attr(feature = "serde", serde(default))]
+        button: MouseButton,
+    },
+    Up {
+        position: Position,
+        #[cfg_attr(feature = "serde", serde(default))]
+        button: MouseButton,
+    },
 }
 
 impl MouseEvent {
@@ -66,6 +246,13 @@ impl MouseEvent {
         matches!(self, Self::Down { .. })
     }
 
+    pub fn button(&self) -> Option<MouseButton> {
+        match self {
+            Self::Down { button, .. } | Self::Up { button, .. } => Some(*button),
+            Self::Move { .. } => None,
+        }
+    }
+
     pub fn position(&self) -> Position {
         match self {
             Self::Up { position, .. } | Self::Down { position, .. } | Self::Move { position } => {
@@ -83,6 +270,19 @@ impl MouseEvent {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Right,
+    Middle,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 #[cfg_attr(
@@ -101,6 +301,14 @@ pub enum Key {
     Tab,
     BackTab,
     Esc,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Space,
+    // Function keys (`F(1)` is F1)
+    F(u8),
     Char(char),
     #[cfg_attr(feature = "serde", serde(other))]
     Other,
@@ -119,3 +327,99 @@ impl TimeoutTag {
         self.0
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum GamepadEvent {
+    Connected {
+        id: GamepadId,
+    },
+    Disconnected {
+        id: GamepadId,
+    },
+    ButtonDown {
+        id: GamepadId,
+        button: GamepadButton,
+    },
+    ButtonUp {
+        id: GamepadId,
+        button: GamepadButton,
+    },
+    // `value` ranges from `-i16::MAX` to `i16::MAX`.
+    AxisMoved {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: i16,
+    },
+}
+
+impl GamepadEvent {
+    pub fn id(&self) -> GamepadId {
+        match self {
+            Self::Connected { id }
+            | Self::Disconnected { id }
+            | Self::ButtonDown { id, .. }
+            | Self::ButtonUp { id, .. }
+            | Self::AxisMoved { id, .. } => *id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadId(u32);
+
+impl GamepadId {
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftShoulder,
+    RightShoulder,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Home,
+    Other(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    Other(u8),
+}
@@ -0,0 +1,162 @@
+use crate::event::{Event, TimeoutTag};
+use crate::{Result, System};
+use orfail::OrFail;
+use std::time::Duration;
+
+pub trait FixedStepHandler<S: System> {
+    fn update(&mut self, system: &mut S, dt: Duration) -> Result<()>;
+    fn render(&mut self, system: &mut S, alpha: f32) -> Result<()>;
+}
+
+#[derive(Debug, Clone)]
+pub struct FixedStepLoop {
+    tag: TimeoutTag,
+    step: Duration,
+    max_catch_up_steps: u32,
+    last_time: Option<Duration>,
+    lag: Duration,
+}
+
+impl FixedStepLoop {
+    pub const DEFAULT_MAX_CATCH_UP_STEPS: u32 = 5;
+
+    pub fn new(tag: TimeoutTag, ticks_per_second: u32) -> Self {
+        Self {
+            tag,
+            step: Self::ticks_to_step(ticks_per_second),
+            max_catch_up_steps: Self::DEFAULT_MAX_CATCH_UP_STEPS,
+            last_time: None,
+            lag: Duration::ZERO,
+        }
+    }
+
+    pub fn tag(&self) -> TimeoutTag {
+        self.tag
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    pub fn set_ticks_per_second(&mut self, ticks_per_second: u32) {
+        self.step = Self::ticks_to_step(ticks_per_second);
+        self.lag = self.lag.min(self.step);
+    }
+
+    pub fn set_max_catch_up_steps(&mut self, n: u32) {
+        self.max_catch_up_steps = n.max(1);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.last_time.is_some()
+    }
+
+    pub fn start<S: System>(&mut self, system: &mut S) {
+        self.last_time = Some(system.clock_game_time());
+        self.lag = Duration::ZERO;
+        system.clock_set_timeout(self.tag, self.step);
+    }
+
+    pub fn stop<S: System>(&mut self, system: &mut S) {
+        self.last_time = None;
+        system.clock_cancel_timeout(self.tag);
+    }
+
+    // Returns `true` if `event` was the timeout event of this loop.
+    pub fn handle_event<S, H>(
+        &mut self,
+        system: &mut S,
+        event: &Event,
+        handler: &mut H,
+    ) -> Result<bool>
+    where
+        S: System,
+        H: FixedStepHandler<S>,
+    {
+        if *event != Event::Timeout(self.tag) {
+            return Ok(false);
+        }
+        let Some(last_time) = self.last_time else {
+            return Ok(true);
+        };
+
+        let now = system.clock_game_time();
+        self.lag += now.saturating_sub(last_time);
+        self.last_time = Some(now);
+
+        let mut steps = 0;
+        while self.lag >= self.step {
+            if steps == self.max_catch_up_steps {
+                // Too far behind: drop the remaining whole steps instead of spiraling.
+                let lag = self.lag.as_nanos() % self.step.as_nanos();
+                self.lag = Duration::from_nanos(lag as u64);
+                break;
+            }
+            handler.update(system, self.step).or_fail()?;
+            self.lag -= self.step;
+            steps += 1;
+        }
+
+        let alpha = self.lag.as_secs_f32() / self.step.as_secs_f32();
+        handler.render(system, alpha).or_fail()?;
+
+        // Waiting only for the remainder of the current step compensates for timer drift.
+        system.clock_set_timeout(self.tag, self.step - self.lag);
+        Ok(true)
+    }
+
+    fn ticks_to_step(ticks_per_second: u32) -> Duration {
+        Duration::from_secs(1) / ticks_per_second.max(1)
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessSystem;
+
+    #[derive(Debug, Default)]
+    struct Counter {
+        updates: u32,
+        renders: u32,
+    }
+
+    impl<S: System> FixedStepHandler<S> for Counter {
+        fn update(&mut self, _system: &mut S, _dt: Duration) -> Result<()> {
+            self.updates += 1;
+            Ok(())
+        }
+
+        fn render(&mut self, _system: &mut S, _alpha: f32) -> Result<()> {
+            self.renders += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn catch_up_steps_are_clamped() -> Result<()> {
+        let mut system = HeadlessSystem::new();
+        let mut game_loop = FixedStepLoop::new(TimeoutTag::new(0), 10);
+        let mut counter = Counter::default();
+        game_loop.start(&mut system);
+
+        let event = system.advance_to_next_timeout().or_fail()?;
+        game_loop
+            .handle_event(&mut system, &event, &mut counter)
+            .or_fail()?;
+        assert_eq!(counter.updates, 1);
+
+        system.advance_clock(Duration::from_secs(2));
+        let event = system.next_timeout_event().or_fail()?;
+        game_loop
+            .handle_event(&mut system, &event, &mut counter)
+            .or_fail()?;
+        assert_eq!(
+            counter.updates,
+            1 + FixedStepLoop::DEFAULT_MAX_CATCH_UP_STEPS
+        );
+        assert_eq!(counter.renders, 2);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    event::{Event, MouseEvent},
+    event::{Event, PointerEvent},
     spatial::{Position, Region, Size},
 };
 
@@ -27,7 +27,19 @@ impl FixedWindow {
 
     pub fn handle_event(&mut self, event: Event) -> Event {
         match event {
-            Event::Mouse(event) => Event::Mouse(self.handle_mouse_event(event)),
+            Event::Pointer(event) => Event::Pointer(self.handle_pointer_event(event)),
+            Event::Wheel {
+                delta_x,
+                delta_y,
+                position,
+            } => {
+                let (scale_x, scale_y) = self.scale();
+                Event::Wheel {
+                    delta_x: (delta_x as f32 * scale_x).round() as i32,
+                    delta_y: (delta_y as f32 * scale_y).round() as i32,
+                    position: self.to_canvas_position(position),
+                }
+            }
             Event::WindowResized(size) => {
                 self.handle_window_resized_event(size);
                 Event::WindowResized(size)
@@ -44,22 +56,29 @@ impl FixedWindow {
         self.canvas_region
     }
 
-    fn handle_mouse_event(&mut self, mut event: MouseEvent) -> MouseEvent {
-        let mut position = event.position();
+    fn handle_pointer_event(&mut self, mut event: PointerEvent) -> PointerEvent {
+        event.position = self.to_canvas_position(event.position);
+        event
+    }
 
+    // Scale factors from the actual window to the logical window
+    fn scale(&self) -> (f32, f32) {
         let logical_window = self.logical_window_size;
         let actual_window = self.actual_window_size;
         let scale_x = logical_window.width as f32 / actual_window.width as f32;
         let scale_y = logical_window.height as f32 / actual_window.height as f32;
+        (scale_x, scale_y)
+    }
+
+    fn to_canvas_position(&self, mut position: Position) -> Position {
+        let (scale_x, scale_y) = self.scale();
 
         position.x = (position.x as f32 * scale_x).round() as i32;
         position.y = (position.y as f32 * scale_y).round() as i32;
 
         position.x -= self.canvas_region.position.x;
         position.y -= self.canvas_region.position.y;
-
-        event.set_position(position);
-        event
+        position
     }
 
     fn handle_window_resized_event(&mut self, size: Size) {
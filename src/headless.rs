@@ -0,0 +1,192 @@
+use crate::audio::{AudioData, AudioSpec, SampleFormat};
+use crate::event::{Event, TimeoutTag};
+use crate::spatial::Size;
+use crate::video::{PixelFormat, VideoFrame, VideoFrameSpec};
+use crate::{Result, System, SystemCapabilities};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone)]
+pub struct HeadlessSystemOptions {
+    pub pixel_format: PixelFormat,
+    pub sample_format: SampleFormat,
+    pub unix_time: Duration,
+    pub storage: BTreeMap<String, Vec<u8>>,
+}
+
+#[derive(Debug, Default)]
+pub struct HeadlessSystem {
+    options: HeadlessSystemOptions,
+    game_time: Duration,
+    timeout_queue: BinaryHeap<Reverse<(Duration, TimeoutTag)>>,
+    pending_timeouts: HashMap<TimeoutTag, Duration>,
+    video_frame: Option<VideoFrame>,
+    audio_data: Vec<AudioData>,
+    text_input_enabled: bool,
+}
+
+impl HeadlessSystem {
+    pub fn with_options(options: HeadlessSystemOptions) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    pub fn new() -> Self {
+        Self::with_options(HeadlessSystemOptions::default())
+    }
+
+    pub fn advance_clock(&mut self, duration: Duration) {
+        self.game_time += duration;
+    }
+
+    pub fn next_timeout_event(&mut self) -> Option<Event> {
+        self.discard_stale_timeouts();
+        let Reverse((expire_time, tag)) = self.timeout_queue.peek().copied()?;
+        if self.game_time < expire_time {
+            return None;
+        }
+        self.timeout_queue.pop();
+        self.pending_timeouts.remove(&tag);
+        Some(Event::Timeout(tag))
+    }
+
+    pub fn advance_to_next_timeout(&mut self) -> Option<Event> {
+        self.discard_stale_timeouts();
+        let Reverse((expire_time, _)) = self.timeout_queue.peek().copied()?;
+        self.game_time = self.game_time.max(expire_time);
+        self.next_timeout_event()
+    }
+
+    pub fn video_frame(&self) -> Option<VideoFrame<&[u8]>> {
+        self.video_frame.as_ref().map(|frame| frame.as_ref())
+    }
+
+    pub fn audio_data(&self) -> &[AudioData] {
+        &self.audio_data
+    }
+
+    pub fn take_audio_data(&mut self) -> Vec<AudioData> {
+        std::mem::take(&mut self.audio_data)
+    }
+
+    pub fn storage(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.options.storage
+    }
+
+    pub fn is_text_input_enabled(&self) -> bool {
+        self.text_input_enabled
+    }
+
+    fn discard_stale_timeouts(&mut self) {
+        while let Some(Reverse((expire_time, tag))) = self.timeout_queue.peek().copied() {
+            if self.pending_timeouts.get(&tag) == Some(&expire_time) {
+                break;
+            }
+            self.timeout_queue.pop();
+        }
+    }
+}
+
+impl System for HeadlessSystem {
+    fn video_init(&mut self, resolution: Size) -> VideoFrameSpec {
+        VideoFrameSpec {
+            pixel_format: self.options.pixel_format,
+            resolution,
+            stride: resolution.width,
+        }
+    }
+
+    fn video_draw(&mut self, frame: VideoFrame<&[u8]>) {
+        self.video_frame = VideoFrame::with_data(frame.spec(), frame.data().to_vec()).ok();
+    }
+
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec {
+        AudioSpec {
+            sample_format: self.options.sample_format,
+            sample_rate,
+            data_samples,
+            channels: channels.max(1),
+        }
+    }
+
+    fn audio_enqueue(&mut self, data: AudioData<&[u8]>) {
+        if let Ok(data) = AudioData::with_data(data.spec(), data.bytes().to_vec()) {
+            self.audio_data.push(data);
+        }
+    }
+
+    fn clock_game_time(&self) -> Duration {
+        self.game_time
+    }
+
+    fn clock_unix_time(&self) -> Duration {
+        self.options.unix_time + self.game_time
+    }
+
+    fn clock_set_timeout(&mut self, tag: TimeoutTag, timeout: Duration) {
+        let expire_time = self.game_time + timeout;
+        self.pending_timeouts.insert(tag, expire_time);
+        self.timeout_queue.push(Reverse((expire_time, tag)));
+    }
+
+    fn clock_cancel_timeout(&mut self, tag: TimeoutTag) {
+        self.pending_timeouts.remove(&tag);
+    }
+
+    fn storage_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.options.storage.get(key).cloned())
+    }
+
+    fn storage_put(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        self.options
+            .storage
+            .insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+
+    fn storage_delete(&mut self, key: &str) -> Result<()> {
+        self.options.storage.remove(key);
+        Ok(())
+    }
+
+    fn text_input_set_enabled(&mut self, enabled: bool) {
+        self.text_input_enabled = enabled;
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        SystemCapabilities {
+            video: true,
+            audio: true,
+            true_color: true,
+            pixel_formats: vec![self.options.pixel_format],
+            sample_formats: vec![self.options.sample_format],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reschedule_and_cancel_timeout_works() {
+        let mut system = HeadlessSystem::new();
+        let tag0 = TimeoutTag::new(0);
+        let tag1 = TimeoutTag::new(1);
+
+        system.clock_set_timeout(tag0, Duration::from_secs(1));
+        system.clock_set_timeout(tag0, Duration::from_secs(3));
+        system.clock_set_timeout(tag1, Duration::from_secs(2));
+        assert_eq!(system.advance_to_next_timeout(), Some(Event::Timeout(tag1)));
+        assert_eq!(system.advance_to_next_timeout(), Some(Event::Timeout(tag0)));
+        assert_eq!(system.clock_game_time(), Duration::from_secs(3));
+
+        system.clock_set_timeout(tag0, Duration::from_secs(1));
+        system.clock_cancel_timeout(tag0);
+        assert_eq!(system.advance_to_next_timeout(), None);
+    }
+}
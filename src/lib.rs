@@ -1,20 +1,29 @@
 use crate::audio::AudioData;
 use crate::audio::AudioSpec;
+use crate::audio::SampleFormat;
 use crate::event::Event;
 use crate::event::TimeoutTag;
-use crate::spatial::Size;
-use crate::video::{VideoFrame, VideoFrameSpec};
+use crate::spatial::{Region, Size};
+use crate::video::{PixelFormat, VideoFrame, VideoFrameSpec};
 use std::time::Duration;
 
 pub mod audio;
+#[cfg(feature = "dispatch")]
+pub mod dispatch;
 pub mod event;
+#[cfg(feature = "fixed_step")]
+pub mod fixed_step;
 #[cfg(feature = "fixed_window")]
 pub mod fixed_window;
+#[cfg(feature = "headless")]
+pub mod headless;
 #[cfg(feature = "image")]
 pub mod image;
 pub mod io;
 #[cfg(feature = "random")]
 pub mod random;
+#[cfg(feature = "record")]
+pub mod record;
 pub mod spatial;
 pub mod video;
 #[cfg(feature = "wasm")]
@@ -25,11 +34,54 @@ pub type Result<T, E = orfail::Failure> = std::result::Result<T, E>;
 pub trait System {
     fn video_init(&mut self, resolution: Size) -> VideoFrameSpec;
     fn video_draw(&mut self, frame: VideoFrame<&[u8]>);
-    fn audio_init(&mut self, sample_rate: u16, data_samples: usize) -> AudioSpec;
+    // Draws only the given regions of `frame` (the rest is assumed to be unchanged since the previous draw).
+    // The default implementation falls back to `System::video_draw()`.
+    fn video_draw_regions(&mut self, frame: VideoFrame<&[u8]>, regions: &[Region]) {
+        let _ = regions;
+        self.video_draw(frame);
+    }
+    // `channels` is a request: the host may return a spec with a different number of channels.
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec;
     fn audio_enqueue(&mut self, data: AudioData<&[u8]>);
     fn clock_game_time(&self) -> Duration;
     fn clock_unix_time(&self) -> Duration;
+    // Schedules an `Event::Timeout` for `tag`.
+    // If a timeout with the same tag is already pending, it is replaced by the new one.
     fn clock_set_timeout(&mut self, tag: TimeoutTag, timeout: Duration);
+
+    // Cancels the pending timeout for `tag`, if any.
+    fn clock_cancel_timeout(&mut self, tag: TimeoutTag);
+    fn storage_get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    fn storage_put(&mut self, key: &str, value: &[u8]) -> Result<()>;
+    fn storage_delete(&mut self, key: &str) -> Result<()>;
+
+    // Enables or disables `Event::TextInput` and `Event::Composition` events.
+    fn text_input_set_enabled(&mut self, enabled: bool);
+
+    fn capabilities(&self) -> SystemCapabilities;
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SystemCapabilities {
+    pub video: bool,
+    pub audio: bool,
+    pub keyboard: bool,
+    pub pointer: bool,
+    pub gamepad: bool,
+
+    // `false` if the host approximates colors (e.g., a 256-color terminal)
+    pub true_color: bool,
+
+    // `None` if unknown or unlimited
+    pub max_resolution: Option<Size>,
+
+    pub pixel_formats: Vec<PixelFormat>,
+    pub sample_formats: Vec<SampleFormat>,
 }
 
 pub trait Game<S: System> {
@@ -45,4 +97,9 @@ pub trait Game<S: System> {
     fn command(&mut self, system: &mut S, name: &str, data: &[u8]) -> Result<()> {
         Err(orfail::Failure::new(format!("unknown command: {name:?}")))
     }
+
+    // Exit status reported by the host after `Game::handle_event()` returned `Ok(false)`.
+    fn exit_code(&self) -> i32 {
+        0
+    }
 }
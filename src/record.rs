@@ -0,0 +1,346 @@
+use crate::audio::{AudioData, AudioSpec};
+use crate::event::{Event, TimeoutTag};
+use crate::spatial::{Region, Size};
+use crate::video::{VideoFrame, VideoFrameSpec};
+use crate::{Game, Result, System, SystemCapabilities};
+use orfail::{Failure, OrFail};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Record {
+    Initialize,
+    #[serde(rename_all = "camelCase")]
+    Event {
+        game_time: Duration,
+        event: Event,
+    },
+    GameTime(Duration),
+    UnixTime(Duration),
+    StorageGet {
+        key: String,
+        value: Option<Vec<u8>>,
+    },
+    Capabilities(SystemCapabilities),
+}
+
+#[derive(Debug)]
+pub struct Recorder<S, W> {
+    inner: S,
+    writer: RefCell<W>,
+    failed: RefCell<Option<Failure>>,
+}
+
+impl<S: System, W: Write> Recorder<S, W> {
+    pub fn new(inner: S, writer: W) -> Self {
+        Self {
+            inner,
+            writer: RefCell::new(writer),
+            failed: RefCell::new(None),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> (S, W) {
+        (self.inner, self.writer.into_inner())
+    }
+
+    pub fn initialize<G: Game<Self>>(&mut self, game: &mut G) -> Result<()> {
+        self.record(&Record::Initialize);
+        game.initialize(self).or_fail()?;
+        self.flush().or_fail()?;
+        Ok(())
+    }
+
+    pub fn handle_event<G: Game<Self>>(&mut self, game: &mut G, event: Event) -> Result<bool> {
+        let game_time = self.inner.clock_game_time();
+        self.record(&Record::Event {
+            game_time,
+            event: event.clone(),
+        });
+        let do_continue = game.handle_event(self, event).or_fail()?;
+        self.flush().or_fail()?;
+        Ok(do_continue)
+    }
+
+    fn record(&self, record: &Record) {
+        let mut failed = self.failed.borrow_mut();
+        if failed.is_some() {
+            return;
+        }
+
+        let mut writer = self.writer.borrow_mut();
+        *failed = serde_json::to_writer(&mut *writer, record)
+            .or_fail()
+            .and_then(|()| writeln!(writer).or_fail())
+            .err();
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(e) = self.failed.get_mut().take() {
+            return Err(e);
+        }
+        self.writer.get_mut().flush().or_fail()?;
+        Ok(())
+    }
+}
+
+impl<S: System, W: Write> System for Recorder<S, W> {
+    fn video_init(&mut self, resolution: Size) -> VideoFrameSpec {
+        self.inner.video_init(resolution)
+    }
+
+    fn video_draw(&mut self, frame: VideoFrame<&[u8]>) {
+        self.inner.video_draw(frame);
+    }
+
+    fn video_draw_regions(&mut self, frame: VideoFrame<&[u8]>, regions: &[Region]) {
+        self.inner.video_draw_regions(frame, regions);
+    }
+
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec {
+        self.inner.audio_init(sample_rate, channels, data_samples)
+    }
+
+    fn audio_enqueue(&mut self, data: AudioData<&[u8]>) {
+        self.inner.audio_enqueue(data);
+    }
+
+    fn clock_game_time(&self) -> Duration {
+        let time = self.inner.clock_game_time();
+        self.record(&Record::GameTime(time));
+        time
+    }
+
+    fn clock_unix_time(&self) -> Duration {
+        let time = self.inner.clock_unix_time();
+        self.record(&Record::UnixTime(time));
+        time
+    }
+
+    fn clock_set_timeout(&mut self, tag: TimeoutTag, timeout: Duration) {
+        self.inner.clock_set_timeout(tag, timeout);
+    }
+
+    fn clock_cancel_timeout(&mut self, tag: TimeoutTag) {
+        self.inner.clock_cancel_timeout(tag);
+    }
+
+    fn storage_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let value = self.inner.storage_get(key).or_fail()?;
+        self.record(&Record::StorageGet {
+            key: key.to_owned(),
+            value: value.clone(),
+        });
+        Ok(value)
+    }
+
+    fn storage_put(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        self.inner.storage_put(key, value).or_fail()
+    }
+
+    fn storage_delete(&mut self, key: &str) -> Result<()> {
+        self.inner.storage_delete(key).or_fail()
+    }
+
+    fn text_input_set_enabled(&mut self, enabled: bool) {
+        self.inner.text_input_set_enabled(enabled);
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        let capabilities = self.inner.capabilities();
+        self.record(&Record::Capabilities(capabilities.clone()));
+        capabilities
+    }
+}
+
+#[derive(Debug)]
+pub struct Player<S> {
+    inner: S,
+    records: RefCell<VecDeque<Record>>,
+    failed: RefCell<Option<Failure>>,
+}
+
+impl<S: System> Player<S> {
+    pub fn new<R: BufRead>(inner: S, reader: R) -> Result<Self> {
+        let mut records = VecDeque::new();
+        for line in reader.lines() {
+            let line = line.or_fail()?;
+            if line.is_empty() {
+                continue;
+            }
+            records.push_back(serde_json::from_str(&line).or_fail()?);
+        }
+        Ok(Self {
+            inner,
+            records: RefCell::new(records),
+            failed: RefCell::new(None),
+        })
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.records.borrow().is_empty()
+    }
+
+    pub fn initialize<G: Game<Self>>(&mut self, game: &mut G) -> Result<()> {
+        let record = self.records.get_mut().pop_front();
+        matches!(record, Some(Record::Initialize)).or_fail_with(|()| {
+            format!("replay diverged: expected an initialize record, but got {record:?}")
+        })?;
+        game.initialize(self).or_fail()?;
+        self.check_failure().or_fail()?;
+        Ok(())
+    }
+
+    pub fn play_next_event<G: Game<Self>>(&mut self, game: &mut G) -> Result<Option<bool>> {
+        let event = match self.records.get_mut().pop_front() {
+            None => return Ok(None),
+            Some(Record::Event { event, .. }) => event,
+            record => {
+                return Err(Failure::new(format!(
+                    "replay diverged: expected an event record, but got {record:?}"
+                )));
+            }
+        };
+        let do_continue = game.handle_event(self, event).or_fail()?;
+        self.check_failure().or_fail()?;
+        Ok(Some(do_continue))
+    }
+
+    pub fn play<G: Game<Self>>(&mut self, game: &mut G) -> Result<()> {
+        while let Some(true) = self.play_next_event(game).or_fail()? {}
+        Ok(())
+    }
+
+    fn check_failure(&mut self) -> Result<()> {
+        if let Some(e) = self.failed.get_mut().take() {
+            return Err(e);
+        }
+        if let Some(record) = self.records.get_mut().front() {
+            if !matches!(record, Record::Initialize | Record::Event { .. }) {
+                return Err(Failure::new(format!(
+                    "replay diverged: unconsumed record {record:?}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn next_record(&self) -> Option<Record> {
+        if self.failed.borrow().is_some() {
+            return None;
+        }
+        self.records.borrow_mut().pop_front()
+    }
+
+    fn diverged(&self, expected: &str, record: Option<Record>) {
+        let mut failed = self.failed.borrow_mut();
+        if failed.is_none() {
+            *failed = Some(Failure::new(format!(
+                "replay diverged: expected {expected} record, but got {record:?}"
+            )));
+        }
+    }
+}
+
+impl<S: System> System for Player<S> {
+    fn video_init(&mut self, resolution: Size) -> VideoFrameSpec {
+        self.inner.video_init(resolution)
+    }
+
+    fn video_draw(&mut self, frame: VideoFrame<&[u8]>) {
+        self.inner.video_draw(frame);
+    }
+
+    fn video_draw_regions(&mut self, frame: VideoFrame<&[u8]>, regions: &[Region]) {
+        self.inner.video_draw_regions(frame, regions);
+    }
+
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec {
+        self.inner.audio_init(sample_rate, channels, data_samples)
+    }
+
+    fn audio_enqueue(&mut self, data: AudioData<&[u8]>) {
+        self.inner.audio_enqueue(data);
+    }
+
+    fn clock_game_time(&self) -> Duration {
+        match self.next_record() {
+            Some(Record::GameTime(time)) => time,
+            record => {
+                self.diverged("a game time", record);
+                Duration::ZERO
+            }
+        }
+    }
+
+    fn clock_unix_time(&self) -> Duration {
+        match self.next_record() {
+            Some(Record::UnixTime(time)) => time,
+            record => {
+                self.diverged("a UNIX time", record);
+                Duration::ZERO
+            }
+        }
+    }
+
+    fn clock_set_timeout(&mut self, _tag: TimeoutTag, _timeout: Duration) {
+        // Timeout events are replayed from the records.
+    }
+
+    fn clock_cancel_timeout(&mut self, _tag: TimeoutTag) {}
+
+    fn storage_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.next_record() {
+            Some(Record::StorageGet { key: k, value }) if k == key => Ok(value),
+            record => {
+                self.diverged(&format!("a storage get ({key:?})"), record);
+                Ok(None)
+            }
+        }
+    }
+
+    fn storage_put(&mut self, _key: &str, _value: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    fn storage_delete(&mut self, _key: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn text_input_set_enabled(&mut self, enabled: bool) {
+        self.inner.text_input_set_enabled(enabled);
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        match self.next_record() {
+            Some(Record::Capabilities(capabilities)) => capabilities,
+            record => {
+                self.diverged("a capabilities", record);
+                SystemCapabilities::default()
+            }
+        }
+    }
+}
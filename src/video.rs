@@ -2,6 +2,9 @@ use crate::spatial::{Position, Size};
 use crate::Result;
 use orfail::{Failure, OrFail};
 
+#[cfg(feature = "image")]
+mod png;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
@@ -31,6 +34,14 @@ pub enum PixelFormat {
     Rgb24 = 0,
     Rgb32 = 1,
     Bgr24 = 2,
+    Bgra32 = 3,
+    Rgba32 = 4,
+
+    // 16-bit little-endian (5 bits red, 6 bits green, 5 bits blue from the most significant bit)
+    Rgb565 = 5,
+
+    // Luma (ITU-R BT.601)
+    Gray8 = 6,
 }
 
 impl PixelFormat {
@@ -39,6 +50,10 @@ impl PixelFormat {
             PixelFormat::Rgb24 => 3,
             PixelFormat::Rgb32 => 4,
             PixelFormat::Bgr24 => 3,
+            PixelFormat::Bgra32 => 4,
+            PixelFormat::Rgba32 => 4,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Gray8 => 1,
         }
     }
 
@@ -51,6 +66,10 @@ impl PixelFormat {
             0 => Ok(Self::Rgb24),
             1 => Ok(Self::Rgb32),
             2 => Ok(Self::Bgr24),
+            3 => Ok(Self::Bgra32),
+            4 => Ok(Self::Rgba32),
+            5 => Ok(Self::Rgb565),
+            6 => Ok(Self::Gray8),
             _ => Err(Failure::new(format!("unknown pixel format: {x}"))),
         }
     }
@@ -96,6 +115,26 @@ impl VideoFrame<Vec<u8>> {
                 d[i * 4 + 1] = g;
                 d[i * 4 + 2] = b;
             }
+            PixelFormat::Bgra32 => {
+                d[i * 4] = b;
+                d[i * 4 + 1] = g;
+                d[i * 4 + 2] = r;
+                d[i * 4 + 3] = 255;
+            }
+            PixelFormat::Rgba32 => {
+                d[i * 4] = r;
+                d[i * 4 + 1] = g;
+                d[i * 4 + 2] = b;
+                d[i * 4 + 3] = 255;
+            }
+            PixelFormat::Rgb565 => {
+                let v = (u16::from(r >> 3) << 11) | (u16::from(g >> 2) << 5) | u16::from(b >> 3);
+                d[i * 2..][..2].copy_from_slice(&v.to_le_bytes());
+            }
+            PixelFormat::Gray8 => {
+                let y = (77 * u32::from(r) + 150 * u32::from(g) + 29 * u32::from(b)) >> 8;
+                d[i] = y as u8;
+            }
         }
     }
 }
@@ -122,6 +161,96 @@ impl<B: AsRef<[u8]>> VideoFrame<B> {
             PixelFormat::Rgb24 => (d[i * 3], d[i * 3 + 1], d[i * 3 + 2]),
             PixelFormat::Bgr24 => (d[i * 3 + 2], d[i * 3 + 1], d[i * 3]),
             PixelFormat::Rgb32 => (d[i * 4], d[i * 4 + 1], d[i * 4 + 2]),
+            PixelFormat::Bgra32 => (d[i * 4 + 2], d[i * 4 + 1], d[i * 4]),
+            PixelFormat::Rgba32 => (d[i * 4], d[i * 4 + 1], d[i * 4 + 2]),
+            PixelFormat::Rgb565 => {
+                let v = u16::from_le_bytes([d[i * 2], d[i * 2 + 1]]);
+                let r = (v >> 11) as u8 & 0x1f;
+                let g = (v >> 5) as u8 & 0x3f;
+                let b = v as u8 & 0x1f;
+                (
+                    (r << 3) | (r >> 2),
+                    (g << 2) | (g >> 4),
+                    (b << 3) | (b >> 2),
+                )
+            }
+            PixelFormat::Gray8 => (d[i], d[i], d[i]),
         }
     }
+
+    // Encodes the frame as an 8-bit RGB PNG image (uncompressed).
+    #[cfg(feature = "image")]
+    pub fn to_png(&self) -> Vec<u8> {
+        let Size { width, height } = self.spec.resolution;
+        let mut scanlines = Vec::with_capacity((width as usize * 3 + 1) * height as usize);
+        for y in 0..height {
+            scanlines.push(0); // Filter type: None
+            for x in 0..width {
+                let (r, g, b) = self.read_rgb(Position::from_xy(x as i32, y as i32));
+                scanlines.extend_from_slice(&[r, g, b]);
+            }
+        }
+        self::png::encode_rgb(width, height, &scanlines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_formats_work() -> Result<()> {
+        for code in 0..=6 {
+            let pixel_format = PixelFormat::from_u8(code).or_fail()?;
+            assert_eq!(pixel_format.as_u8(), code);
+
+            let spec = VideoFrameSpec {
+                pixel_format,
+                resolution: Size::from_wh(2, 1),
+                stride: 2,
+            };
+            let mut frame = VideoFrame::new(spec);
+            assert_eq!(frame.data().len(), spec.data_len());
+
+            let pos = Position::from_xy(1, 0);
+            frame.write_rgb(pos, 255, 128, 0);
+            let expected = match pixel_format {
+                PixelFormat::Rgb565 => (255, 130, 0),
+                PixelFormat::Gray8 => (151, 151, 151),
+                _ => (255, 128, 0),
+            };
+            assert_eq!(frame.read_rgb(pos), expected, "{pixel_format:?}");
+            assert_eq!(frame.read_rgb(Position::ORIGIN), (255, 255, 255));
+        }
+        assert!(PixelFormat::from_u8(7).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn to_png_works() -> Result<()> {
+        let spec = VideoFrameSpec {
+            pixel_format: PixelFormat::Rgb565,
+            resolution: Size::from_wh(2, 2),
+            stride: 3,
+        };
+        let mut frame = VideoFrame::new(spec);
+        frame.write_rgb(Position::from_xy(1, 1), 255, 0, 0);
+
+        let png = frame.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], [0, 0, 0, 2, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..], b"IEND\xae\x42\x60\x82");
+
+        // IDAT: length, "IDAT", zlib header and a single stored block
+        let idat = &png[33..];
+        assert_eq!(&idat[4..8], b"IDAT");
+        let scanlines = &idat[15..][..14];
+        assert_eq!(
+            scanlines,
+            [0, 255, 255, 255, 255, 255, 255, 0, 255, 255, 255, 255, 0, 0]
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,94 @@
+// Minimal PNG encoder (8-bit RGB, no compression).
+//
+// See: https://www.w3.org/TR/png/
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+const COLOR_TYPE_RGB: u8 = 2;
+const MAX_STORED_BLOCK_LEN: usize = 0xffff;
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+// `scanlines` must contain the filter-type byte (0 = none) followed by the RGB bytes for each row.
+pub fn encode_rgb(width: u32, height: u32, scanlines: &[u8]) -> Vec<u8> {
+    let mut png = SIGNATURE.to_vec();
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[8, COLOR_TYPE_RGB, 0, 0, 0]);
+    write_chunk(&mut png, b"IHDR", &ihdr);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// zlib stream consisting of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut blocks = data.chunks(MAX_STORED_BLOCK_LEN).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        blocks.push(&[]);
+    }
+
+    let mut zlib = Vec::with_capacity(2 + blocks.len() * 5 + data.len() + 4);
+    zlib.extend_from_slice(&[0x78, 0x01]);
+    for (i, block) in blocks.iter().enumerate() {
+        let is_final = i + 1 == blocks.len();
+        let len = block.len() as u16;
+        zlib.push(u8::from(is_final));
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut c = 0xffff_ffff;
+    for &b in data {
+        c = CRC_TABLE[((c ^ u32::from(b)) & 0xff) as usize] ^ (c >> 8);
+    }
+    c ^ 0xffff_ffff
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the largest n such that the sums never overflow before the reduction.
+    for chunk in data.chunks(5552) {
+        for &x in chunk {
+            a += u32::from(x);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
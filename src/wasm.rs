@@ -1,9 +1,9 @@
 #![allow(clippy::missing_safety_doc)] // FIXME
 use crate::audio::{AudioSpec, SampleFormat};
 use crate::event::{Event, TimeoutTag};
-use crate::spatial::Size;
+use crate::spatial::{Region, Size};
 use crate::video::{PixelFormat, VideoFrameSpec};
-use crate::{audio::AudioData, video::VideoFrame, Game, System};
+use crate::{audio::AudioData, video::VideoFrame, Game, Result, System, SystemCapabilities};
 use orfail::{Failure, OrFail};
 use std::time::Duration;
 
@@ -101,6 +101,14 @@ where
     }
 }
 
+pub unsafe fn game_exit_code<G>(game: *mut G) -> i32
+where
+    G: Game<WasmSystem>,
+{
+    let game = &*game;
+    game.exit_code()
+}
+
 pub fn memory_allocate_bytes(size: i32) -> *mut Vec<u8> {
     Box::into_raw(Box::new(vec![0u8; size as usize]))
 }
@@ -170,6 +178,11 @@ macro_rules! export_wasm_functions {
             $crate::wasm::game_command(game, name, data)
         }
 
+        #[no_mangle]
+        pub unsafe fn gameExitCode(game: *mut $game) -> i32 {
+            $crate::wasm::game_exit_code(game)
+        }
+
         #[no_mangle]
         pub fn memoryAllocateBytes(size: i32) -> *mut Vec<u8> {
             $crate::wasm::memory_allocate_bytes(size)
@@ -234,22 +247,77 @@ impl System for WasmSystem {
         unsafe { systemVideoDraw(data.as_ptr(), data.len(), width, stride, format) }
     }
 
-    fn audio_init(&mut self, sample_rate: u16, data_samples: usize) -> AudioSpec {
+    fn video_draw_regions(&mut self, frame: VideoFrame<&[u8]>, regions: &[Region]) {
+        extern "C" {
+            fn systemVideoDrawRegions(
+                data: *const u8,
+                data_len: usize,
+                width: u32,
+                stride: u32,
+                format: u32,
+                regions: *const u32,
+                regions_len: usize,
+            );
+        }
+
+        // Flattened `[x, y, width, height]` of the regions clipped to the frame
+        let frame_region = Region::from(frame.spec().resolution);
+        let regions = regions
+            .iter()
+            .map(|r| r.intersection(frame_region))
+            .filter(|r| !r.is_empty())
+            .flat_map(|r| {
+                let (x, y) = (r.position.x as u32, r.position.y as u32);
+                [x, y, r.size.width, r.size.height]
+            })
+            .collect::<Vec<_>>();
+        if regions.is_empty() {
+            return;
+        }
+
+        let data = frame.data();
+        let width = frame.spec().resolution.width;
+        let stride = frame.spec().stride;
+        let format = u32::from(frame.spec().pixel_format.as_u8());
+        unsafe {
+            systemVideoDrawRegions(
+                data.as_ptr(),
+                data.len(),
+                width,
+                stride,
+                format,
+                regions.as_ptr(),
+                regions.len() / 4,
+            )
+        }
+    }
+
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec {
         extern "C" {
-            fn systemAudioInit(sample_rate: u32, data_samples: u32, sample_format: *mut u8);
+            fn systemAudioInit(
+                sample_rate: u32,
+                data_samples: u32,
+                sample_format: *mut u8,
+                channels: *mut u8,
+            );
         }
         let mut sample_format = 0;
+
+        // The host overwrites `channels` with the number of channels it supports.
+        let mut channels = channels.max(1);
         unsafe {
             systemAudioInit(
                 u32::from(sample_rate),
                 data_samples as u32,
                 &mut sample_format,
+                &mut channels,
             )
         };
         AudioSpec {
             sample_rate,
             data_samples,
             sample_format: SampleFormat::from_u8(sample_format).unwrap_or_else(|e| panic!("{e}")),
+            channels,
         }
     }
 
@@ -284,4 +352,113 @@ impl System for WasmSystem {
             systemClockSetTimeout(tag.get(), timeout.as_secs_f64());
         }
     }
+
+    fn clock_cancel_timeout(&mut self, tag: TimeoutTag) {
+        extern "C" {
+            fn systemClockCancelTimeout(tag: u32);
+        }
+        unsafe {
+            systemClockCancelTimeout(tag.get());
+        }
+    }
+
+    fn storage_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        extern "C" {
+            fn systemStorageGetSize(key: *const u8, key_len: i32) -> i32;
+            fn systemStorageGet(key: *const u8, key_len: i32, data: *mut u8);
+        }
+        let size = unsafe { systemStorageGetSize(key.as_ptr(), key.len() as i32) };
+        if size < 0 {
+            return Ok(None);
+        }
+        let mut data = vec![0; size as usize];
+        unsafe { systemStorageGet(key.as_ptr(), key.len() as i32, data.as_mut_ptr()) };
+        Ok(Some(data))
+    }
+
+    fn storage_put(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        extern "C" {
+            fn systemStoragePut(key: *const u8, key_len: i32, data: *const u8, data_len: i32);
+        }
+        unsafe {
+            systemStoragePut(
+                key.as_ptr(),
+                key.len() as i32,
+                value.as_ptr(),
+                value.len() as i32,
+            )
+        };
+        Ok(())
+    }
+
+    fn storage_delete(&mut self, key: &str) -> Result<()> {
+        extern "C" {
+            fn systemStorageDelete(key: *const u8, key_len: i32);
+        }
+        unsafe { systemStorageDelete(key.as_ptr(), key.len() as i32) };
+        Ok(())
+    }
+
+    fn text_input_set_enabled(&mut self, enabled: bool) {
+        extern "C" {
+            fn systemTextInputSetEnabled(enabled: i32);
+        }
+        unsafe { systemTextInputSetEnabled(enabled as i32) };
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        extern "C" {
+            fn systemCapabilities(
+                flags: *mut u32,
+                max_width: *mut u32,
+                max_height: *mut u32,
+                pixel_formats: *mut u32,
+                sample_formats: *mut u32,
+            );
+        }
+
+        let mut flags = 0;
+        let mut max_width = 0;
+        let mut max_height = 0;
+        let mut pixel_formats = 0;
+        let mut sample_formats = 0;
+        unsafe {
+            systemCapabilities(
+                &mut flags,
+                &mut max_width,
+                &mut max_height,
+                &mut pixel_formats,
+                &mut sample_formats,
+            )
+        };
+
+        // `pixel_formats` and `sample_formats` are bitmasks of `1 << format.as_u8()`.
+        let pixel_formats = (0..32)
+            .filter(|i| pixel_formats & (1 << i) != 0)
+            .filter_map(|i| PixelFormat::from_u8(i).ok())
+            .collect();
+        let sample_formats = (0..32)
+            .filter(|i| sample_formats & (1 << i) != 0)
+            .filter_map(|i| SampleFormat::from_u8(i).ok())
+            .collect();
+        SystemCapabilities {
+            video: flags & CAPABILITY_VIDEO != 0,
+            audio: flags & CAPABILITY_AUDIO != 0,
+            keyboard: flags & CAPABILITY_KEYBOARD != 0,
+            pointer: flags & CAPABILITY_POINTER != 0,
+            gamepad: flags & CAPABILITY_GAMEPAD != 0,
+            true_color: flags & CAPABILITY_TRUE_COLOR != 0,
+            max_resolution: (max_width != 0 && max_height != 0)
+                .then(|| Size::from_wh(max_width, max_height)),
+            pixel_formats,
+            sample_formats,
+        }
+    }
 }
+
+const CAPABILITY_VIDEO: u32 = 1 << 0;
+const CAPABILITY_AUDIO: u32 = 1 << 1;
+const CAPABILITY_KEYBOARD: u32 = 1 << 2;
+const CAPABILITY_POINTER: u32 = 1 << 3;
+const CAPABILITY_GAMEPAD: u32 = 1 << 4;
+const CAPABILITY_TRUE_COLOR: u32 = 1 << 5;
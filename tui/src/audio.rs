@@ -1,7 +1,7 @@
 use libpulse_binding as pulseaudio;
 use libpulse_simple_binding as pulseaudio_simple;
 use orfail::OrFail;
-use pagurus::audio::{AudioData, AudioSpec};
+use pagurus::audio::AudioData;
 
 pub struct AudioSystem {
     simple: pulseaudio_simple::Simple,
@@ -9,12 +9,13 @@ pub struct AudioSystem {
 }
 
 impl AudioSystem {
-    pub fn new(sample_rate: u16) -> orfail::Result<Self> {
+    pub fn new(sample_rate: u16, channels: u8) -> orfail::Result<Self> {
         let pulseaudio_spec = pulseaudio::sample::Spec {
             format: pulseaudio::sample::Format::S16be,
             rate: sample_rate as u32,
-            channels: AudioSpec::CHANNELS,
+            channels,
         };
+        pulseaudio_spec.is_valid().or_fail()?;
         let simple = pulseaudio_simple::Simple::new(
             None,
             "Pagurus",
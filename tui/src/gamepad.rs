@@ -0,0 +1,182 @@
+use pagurus::event::{Event, GamepadAxis, GamepadButton, GamepadEvent, GamepadId};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::Read,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::Duration,
+};
+
+// See: https://www.kernel.org/doc/Documentation/input/joystick-api.txt
+const JS_EVENT_BUTTON: u8 = 0x01;
+const JS_EVENT_AXIS: u8 = 0x02;
+const JS_EVENT_INIT: u8 = 0x80;
+
+const MAX_DEVICES: u32 = 8;
+const SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+// The scan thread stops when the returned watcher is dropped.
+pub fn spawn_device_watcher(tx: mpsc::Sender<Event>) -> DeviceWatcher {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let watcher = DeviceWatcher {
+        stopped: Arc::clone(&stopped),
+    };
+    std::thread::spawn(move || watch_devices(tx, &stopped));
+    watcher
+}
+
+#[derive(Debug)]
+pub struct DeviceWatcher {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+fn watch_devices(tx: mpsc::Sender<Event>, stopped: &AtomicBool) {
+    let connected = Arc::new(Mutex::new(HashSet::new()));
+    while !stopped.load(Ordering::SeqCst) {
+        for i in 0..MAX_DEVICES {
+            let Ok(mut connected_ids) = connected.lock() else {
+                return;
+            };
+            if connected_ids.contains(&i) {
+                continue;
+            }
+            let Ok(file) = File::open(format!("/dev/input/js{i}")) else {
+                continue;
+            };
+
+            let id = GamepadId::new(i);
+            if tx
+                .send(Event::Gamepad(GamepadEvent::Connected { id }))
+                .is_err()
+            {
+                return;
+            }
+            connected_ids.insert(i);
+
+            let tx = tx.clone();
+            let connected = Arc::clone(&connected);
+            std::thread::spawn(move || {
+                read_device_events(id, file, &tx);
+                if let Ok(mut connected_ids) = connected.lock() {
+                    connected_ids.remove(&i);
+                }
+                let _ = tx.send(Event::Gamepad(GamepadEvent::Disconnected { id }));
+            });
+        }
+        std::thread::sleep(SCAN_INTERVAL);
+    }
+}
+
+fn read_device_events(id: GamepadId, mut file: File, tx: &mpsc::Sender<Event>) {
+    let mut buf = [0; 8];
+    while file.read_exact(&mut buf).is_ok() {
+        if let Some(event) = parse_js_event(id, buf) {
+            if tx.send(Event::Gamepad(event)).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+fn parse_js_event(id: GamepadId, buf: [u8; 8]) -> Option<GamepadEvent> {
+    // struct js_event { __u32 time; __s16 value; __u8 type; __u8 number; };
+    let value = i16::from_ne_bytes([buf[4], buf[5]]);
+    let kind = buf[6];
+    let number = buf[7];
+
+    if kind & JS_EVENT_INIT != 0 {
+        // Synthetic events reporting the initial device state.
+        return None;
+    }
+
+    match kind {
+        JS_EVENT_BUTTON => {
+            let button = to_gamepad_button(number);
+            if value != 0 {
+                Some(GamepadEvent::ButtonDown { id, button })
+            } else {
+                Some(GamepadEvent::ButtonUp { id, button })
+            }
+        }
+        JS_EVENT_AXIS => Some(GamepadEvent::AxisMoved {
+            id,
+            axis: to_gamepad_axis(number),
+            value: value.max(-i16::MAX),
+        }),
+        _ => None,
+    }
+}
+
+// The layout commonly reported by the `xpad` driver.
+fn to_gamepad_button(number: u8) -> GamepadButton {
+    match number {
+        0 => GamepadButton::South,
+        1 => GamepadButton::East,
+        2 => GamepadButton::West,
+        3 => GamepadButton::North,
+        4 => GamepadButton::LeftShoulder,
+        5 => GamepadButton::RightShoulder,
+        6 => GamepadButton::Select,
+        7 => GamepadButton::Start,
+        8 => GamepadButton::Home,
+        9 => GamepadButton::LeftStick,
+        10 => GamepadButton::RightStick,
+        n => GamepadButton::Other(n),
+    }
+}
+
+fn to_gamepad_axis(number: u8) -> GamepadAxis {
+    match number {
+        0 => GamepadAxis::LeftStickX,
+        1 => GamepadAxis::LeftStickY,
+        3 => GamepadAxis::RightStickX,
+        4 => GamepadAxis::RightStickY,
+        n => GamepadAxis::Other(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn js_event(value: i16, kind: u8, number: u8) -> [u8; 8] {
+        let mut buf = [0; 8];
+        buf[4..6].copy_from_slice(&value.to_ne_bytes());
+        buf[6] = kind;
+        buf[7] = number;
+        buf
+    }
+
+    #[test]
+    fn parse_js_event_works() {
+        let id = GamepadId::new(0);
+        assert_eq!(
+            parse_js_event(id, js_event(1, JS_EVENT_BUTTON, 0)),
+            Some(GamepadEvent::ButtonDown {
+                id,
+                button: GamepadButton::South
+            })
+        );
+        assert_eq!(
+            parse_js_event(id, js_event(i16::MIN, JS_EVENT_AXIS, 1)),
+            Some(GamepadEvent::AxisMoved {
+                id,
+                axis: GamepadAxis::LeftStickY,
+                value: -i16::MAX
+            })
+        );
+        assert_eq!(
+            parse_js_event(id, js_event(0, JS_EVENT_BUTTON | JS_EVENT_INIT, 0)),
+            None
+        );
+    }
+}
@@ -1,20 +1,31 @@
 #[cfg(feature = "audio")]
 mod audio;
 
+#[cfg(all(feature = "gamepad", target_os = "linux"))]
+mod gamepad;
+
+#[cfg(all(feature = "video", unix))]
+mod signal;
+
 #[cfg(feature = "video")]
 mod video;
 
+mod storage;
+
 use orfail::{Failure, OrFail};
 use pagurus::{
-    audio::{AudioData, AudioSpec, SampleFormat},
+    audio::{AudioData, AudioSpec, SampleFormat, WavWriter},
     event::{Event, TimeoutTag},
-    spatial::Size,
+    spatial::{Region, Size},
     video::{PixelFormat, VideoFrame, VideoFrameSpec},
-    System,
+    System, SystemCapabilities,
 };
 use std::{
     cmp::Reverse,
-    collections::BinaryHeap,
+    collections::{BinaryHeap, HashMap},
+    fs::File,
+    io::BufWriter,
+    path::PathBuf,
     time::{Duration, Instant, UNIX_EPOCH},
 };
 
@@ -24,35 +35,70 @@ pub struct TuiSystemOptions {
     pub disable_mouse: bool,
     #[cfg(feature = "video")]
     pub disable_alternate_screen: bool,
+    #[cfg(feature = "gamepad")]
+    pub disable_gamepad: bool,
+
+    // Defaults to `$XDG_DATA_HOME/pagurus/${EXECUTABLE_NAME}/`.
+    pub storage_dir: Option<PathBuf>,
+
+    // If specified, the audio output is written to this WAV file instead of being played.
+    pub audio_file: Option<PathBuf>,
 }
 
 #[derive(Debug)]
 pub struct TuiSystem {
     start_time: Instant,
     timeout_queue: BinaryHeap<Reverse<(Duration, TimeoutTag)>>,
+    pending_timeouts: HashMap<TimeoutTag, Duration>,
     failed: Option<Failure>,
+    storage: self::storage::Storage,
+    capabilities: SystemCapabilities,
+    audio_file_path: Option<PathBuf>,
+    audio_file: Option<WavWriter<BufWriter<File>>>,
 
     #[cfg(feature = "audio")]
     audio: Option<self::audio::AudioSystem>,
 
     #[cfg(feature = "video")]
     video: self::video::VideoSystem,
+
+    #[cfg(all(feature = "video", unix))]
+    _signal_watcher: Option<self::signal::SignalWatcher>,
+
+    #[cfg(all(feature = "gamepad", target_os = "linux"))]
+    _device_watcher: Option<self::gamepad::DeviceWatcher>,
 }
 
 impl TuiSystem {
-    #[cfg_attr(not(feature = "video"), allow(unused_variables))]
     pub fn with_options(options: TuiSystemOptions) -> pagurus::Result<Self> {
-        Ok(Self {
+        #[cfg(feature = "video")]
+        let video = self::video::VideoSystem::new(options.clone()).or_fail()?;
+
+        let this = Self {
             start_time: Instant::now(),
             timeout_queue: BinaryHeap::new(),
+            pending_timeouts: HashMap::new(),
             failed: None,
+            storage: self::storage::Storage::new(options.storage_dir.clone()),
+            capabilities: Self::probe_capabilities(&options),
+            audio_file_path: options.audio_file.clone(),
+            audio_file: None,
 
             #[cfg(feature = "audio")]
             audio: None,
 
+            #[cfg(all(feature = "video", unix))]
+            _signal_watcher: self::signal::spawn_signal_watcher(video.event_sender()).or_fail()?,
+
+            #[cfg(all(feature = "gamepad", target_os = "linux"))]
+            _device_watcher: (!options.disable_gamepad)
+                .then(|| self::gamepad::spawn_device_watcher(video.event_sender())),
+
             #[cfg(feature = "video")]
-            video: self::video::VideoSystem::new(options).or_fail()?,
-        })
+            video,
+        };
+
+        Ok(this)
     }
 
     pub fn new() -> pagurus::Result<Self> {
@@ -64,6 +110,7 @@ impl TuiSystem {
             return Err(e);
         }
 
+        self.discard_stale_timeouts();
         if let Some(Reverse((expire_time, tag))) = self.timeout_queue.peek().copied() {
             let now = self.clock_game_time();
             if let Some(timeout) = expire_time.checked_sub(now) {
@@ -72,12 +119,54 @@ impl TuiSystem {
                 }
             }
             self.timeout_queue.pop();
+            self.pending_timeouts.remove(&tag);
             Ok(Event::Timeout(tag))
         } else {
             self.next_video_event(None).or_fail()
         }
     }
 
+    fn probe_capabilities(options: &TuiSystemOptions) -> SystemCapabilities {
+        let mut capabilities = SystemCapabilities::default();
+
+        #[cfg(feature = "video")]
+        {
+            capabilities.video = true;
+            capabilities.keyboard = true;
+            capabilities.pointer = !options.disable_mouse;
+            capabilities.true_color =
+                std::env::var("COLORTERM").is_ok_and(|v| v == "truecolor" || v == "24bit");
+            capabilities.pixel_formats = vec![PixelFormat::Rgb24];
+        }
+
+        #[cfg(feature = "audio")]
+        {
+            capabilities.audio = true;
+            capabilities.sample_formats = vec![SampleFormat::I16Be];
+        }
+
+        #[cfg(all(feature = "gamepad", target_os = "linux"))]
+        {
+            capabilities.gamepad = !options.disable_gamepad;
+        }
+
+        if options.audio_file.is_some() {
+            capabilities.audio = true;
+            capabilities.sample_formats = vec![SampleFormat::I16Le];
+        }
+
+        capabilities
+    }
+
+    fn discard_stale_timeouts(&mut self) {
+        while let Some(Reverse((expire_time, tag))) = self.timeout_queue.peek().copied() {
+            if self.pending_timeouts.get(&tag) == Some(&expire_time) {
+                break;
+            }
+            self.timeout_queue.pop();
+        }
+    }
+
     #[cfg_attr(not(feature = "video"), allow(unused_variables))]
     fn next_video_event(&mut self, timeout: Option<Duration>) -> orfail::Result<Event> {
         #[cfg(not(feature = "video"))]
@@ -119,9 +208,40 @@ impl System for TuiSystem {
         }
     }
 
-    fn audio_init(&mut self, sample_rate: u16, data_samples: usize) -> AudioSpec {
+    #[cfg_attr(not(feature = "video"), allow(unused_variables))]
+    fn video_draw_regions(&mut self, frame: VideoFrame<&[u8]>, regions: &[Region]) {
+        if self.failed.is_none() {
+            #[cfg(feature = "video")]
+            {
+                self.failed = self.video.draw_regions(frame, regions).err();
+            }
+        }
+    }
+
+    fn audio_init(&mut self, sample_rate: u16, channels: u8, data_samples: usize) -> AudioSpec {
+        // Only mono and stereo are supported (as in the web host).
+        let channels = channels.clamp(1, 2);
+
+        if let Some(path) = &self.audio_file_path {
+            let spec = AudioSpec {
+                sample_format: SampleFormat::I16Le,
+                sample_rate,
+                data_samples,
+                channels,
+            };
+            match File::create(path)
+                .map(BufWriter::new)
+                .or_fail_with(|e| format!("failed to create {path:?}: {e}"))
+                .and_then(|file| WavWriter::new(file, spec).or_fail())
+            {
+                Ok(writer) => self.audio_file = Some(writer),
+                Err(e) => self.failed = Some(e),
+            }
+            return spec;
+        }
+
         #[cfg(feature = "audio")]
-        match self::audio::AudioSystem::new(sample_rate) {
+        match self::audio::AudioSystem::new(sample_rate, channels) {
             Err(e) => {
                 self.failed = Some(e);
             }
@@ -134,21 +254,25 @@ impl System for TuiSystem {
             sample_format: SampleFormat::I16Be,
             sample_rate,
             data_samples,
+            channels,
         }
     }
 
-    #[cfg_attr(not(feature = "audio"), allow(unused_variables))]
     fn audio_enqueue(&mut self, data: AudioData<&[u8]>) {
         if self.failed.is_some() {
             return;
         }
 
-        #[cfg(feature = "audio")]
-        {
-            self.failed = self
-                .audio
-                .as_mut()
-                .and_then(|a| a.enqueue(data).or_fail().err());
+        if let Some(writer) = &mut self.audio_file {
+            self.failed = writer.write(&data).or_fail().err();
+        } else {
+            #[cfg(feature = "audio")]
+            {
+                self.failed = self
+                    .audio
+                    .as_mut()
+                    .and_then(|a| a.enqueue(data).or_fail().err());
+            }
         }
     }
 
@@ -161,7 +285,46 @@ impl System for TuiSystem {
     }
 
     fn clock_set_timeout(&mut self, tag: TimeoutTag, timeout: Duration) {
-        let now = self.clock_game_time();
-        self.timeout_queue.push(Reverse((now + timeout, tag)));
+        let expire_time = self.clock_game_time() + timeout;
+        self.pending_timeouts.insert(tag, expire_time);
+        self.timeout_queue.push(Reverse((expire_time, tag)));
+    }
+
+    fn clock_cancel_timeout(&mut self, tag: TimeoutTag) {
+        self.pending_timeouts.remove(&tag);
+    }
+
+    fn storage_get(&self, key: &str) -> pagurus::Result<Option<Vec<u8>>> {
+        self.storage.get(key).or_fail()
+    }
+
+    fn storage_put(&mut self, key: &str, value: &[u8]) -> pagurus::Result<()> {
+        self.storage.put(key, value).or_fail()
+    }
+
+    fn storage_delete(&mut self, key: &str) -> pagurus::Result<()> {
+        self.storage.delete(key).or_fail()
+    }
+
+    #[cfg_attr(not(feature = "video"), allow(unused_variables))]
+    fn text_input_set_enabled(&mut self, enabled: bool) {
+        if self.failed.is_none() {
+            #[cfg(feature = "video")]
+            {
+                self.failed = self.video.set_text_input_enabled(enabled).err();
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "video"), allow(unused_mut))]
+    fn capabilities(&self) -> SystemCapabilities {
+        let mut capabilities = self.capabilities.clone();
+
+        #[cfg(feature = "video")]
+        {
+            capabilities.max_resolution = self::video::VideoSystem::terminal_size().ok();
+        }
+
+        capabilities
     }
 }
@@ -0,0 +1,92 @@
+use orfail::OrFail;
+use pagurus::event::Event;
+use std::{
+    fs::File,
+    io::Read,
+    os::fd::FromRawFd,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        mpsc,
+    },
+};
+
+static SIGNAL_PIPE_WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+
+const SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+// Converts SIGINT and SIGTERM into `Event::TerminateRequested` (the self-pipe trick).
+//
+// The previous signal handlers are restored when the returned watcher is dropped.
+pub fn spawn_signal_watcher(tx: mpsc::Sender<Event>) -> orfail::Result<Option<SignalWatcher>> {
+    let mut fds = [0; 2];
+    (unsafe { libc::pipe(fds.as_mut_ptr()) } == 0)
+        .or_fail_with(|()| std::io::Error::last_os_error().to_string())?;
+    let [read_fd, write_fd] = fds;
+    if SIGNAL_PIPE_WRITE_FD
+        .compare_exchange(-1, write_fd, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        // Another `TuiSystem` instance has already installed the handlers.
+        unsafe {
+            libc::close(read_fd);
+            libc::close(write_fd);
+        }
+        return Ok(None);
+    }
+
+    let mut pipe = unsafe { File::from_raw_fd(read_fd) };
+    std::thread::spawn(move || {
+        let mut buf = [0; 1];
+        while pipe.read_exact(&mut buf).is_ok() {
+            if tx.send(Event::TerminateRequested).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut watcher = SignalWatcher {
+        write_fd,
+        previous_handlers: Vec::new(),
+    };
+    for signum in SIGNALS {
+        let handler = handle_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        let previous = unsafe { libc::signal(signum, handler) };
+        (previous != libc::SIG_ERR)
+            .or_fail_with(|()| std::io::Error::last_os_error().to_string())?;
+        watcher.previous_handlers.push((signum, previous));
+    }
+    Ok(Some(watcher))
+}
+
+#[derive(Debug)]
+pub struct SignalWatcher {
+    write_fd: libc::c_int,
+    previous_handlers: Vec<(libc::c_int, libc::sighandler_t)>,
+}
+
+impl Drop for SignalWatcher {
+    fn drop(&mut self) {
+        for &(signum, handler) in &self.previous_handlers {
+            unsafe {
+                libc::signal(signum, handler);
+            }
+        }
+
+        // Closing the write end also terminates the watcher thread.
+        SIGNAL_PIPE_WRITE_FD.store(-1, Ordering::SeqCst);
+        unsafe {
+            libc::close(self.write_fd);
+        }
+    }
+}
+
+extern "C" fn handle_signal(_signum: libc::c_int) {
+    // Only async-signal-safe operations are allowed here.
+    let fd = SIGNAL_PIPE_WRITE_FD.load(Ordering::SeqCst);
+    if fd < 0 {
+        return;
+    }
+    unsafe {
+        libc::write(fd, [0u8].as_ptr().cast(), 1);
+    }
+}
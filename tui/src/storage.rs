@@ -0,0 +1,75 @@
+use orfail::OrFail;
+use std::{io::ErrorKind, path::PathBuf};
+
+#[derive(Debug)]
+pub struct Storage {
+    dir: Option<PathBuf>,
+}
+
+impl Storage {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: dir.or_else(default_storage_dir),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> orfail::Result<Option<Vec<u8>>> {
+        match std::fs::read(self.path(key).or_fail()?) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).or_fail(),
+        }
+    }
+
+    pub fn put(&mut self, key: &str, value: &[u8]) -> orfail::Result<()> {
+        let path = self.path(key).or_fail()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).or_fail()?;
+        }
+
+        // Write to a temporary file first so that a crash never leaves a truncated entry.
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, value).or_fail()?;
+        std::fs::rename(&tmp_path, &path).or_fail()?;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, key: &str) -> orfail::Result<()> {
+        match std::fs::remove_file(self.path(key).or_fail()?) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e).or_fail(),
+            _ => Ok(()),
+        }
+    }
+
+    fn path(&self, key: &str) -> orfail::Result<PathBuf> {
+        let dir = self
+            .dir
+            .as_ref()
+            .or_fail_with(|()| "cannot determine the storage directory".to_owned())?;
+        Ok(dir.join(format!("{}.data", escape_key(key))))
+    }
+}
+
+fn default_storage_dir() -> Option<PathBuf> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+        })?;
+    let game_name = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem().map(|name| name.to_owned()))?;
+    Some(data_dir.join("pagurus").join(game_name))
+}
+
+fn escape_key(key: &str) -> String {
+    let mut escaped = String::new();
+    for b in key.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+            escaped.push(char::from(b));
+        } else {
+            escaped.push_str(&format!("%{b:02X}"));
+        }
+    }
+    escaped
+}
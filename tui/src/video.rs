@@ -1,11 +1,21 @@
 use crate::TuiSystemOptions;
 use orfail::{Failure, OrFail};
 use pagurus::{
-    event::{Event, Key, KeyEvent, MouseEvent},
-    spatial::{Position, Size},
+    event::{
+        Event, Key, KeyEvent, MouseButton, PointerAction, PointerButtons, PointerEvent, PointerId,
+        PointerKind,
+    },
+    spatial::{Position, Region, Size},
     video::VideoFrame,
 };
-use std::{collections::BTreeMap, io::Write, sync::mpsc};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+};
 use termion::{
     color::{Bg, Fg, Rgb},
     cursor::HideCursor,
@@ -20,6 +30,7 @@ pub struct VideoSystem {
     dirty_pixels: BTreeMap<DirtyPixelsKey, UpperLowerPixels>,
     frame_buffer: FrameBuffer,
     stdout: Box<dyn 'static + Write>,
+    text_input_enabled: Arc<AtomicBool>,
 }
 
 impl VideoSystem {
@@ -38,6 +49,7 @@ impl VideoSystem {
             stdout = Box::new(stdout.into_alternate_screen().or_fail()?);
         }
         write!(stdout, "{}", termion::clear::All).or_fail()?;
+        write!(stdout, "{FOCUS_REPORTING_ON}").or_fail()?;
         stdout.flush().or_fail()?;
 
         let terminal_size = Self::terminal_size().or_fail()?;
@@ -45,7 +57,9 @@ impl VideoSystem {
         let (tx, rx) = mpsc::channel();
         let _ = tx.send(Event::WindowResized(terminal_size));
         let event_sender = tx.clone();
-        std::thread::spawn(move || listen_input_events(tx));
+        let text_input_enabled = Arc::new(AtomicBool::new(false));
+        let text_input_enabled_for_input = Arc::clone(&text_input_enabled);
+        std::thread::spawn(move || listen_input_events(tx, text_input_enabled_for_input));
 
         let mut frame_buffer = FrameBuffer::default();
         frame_buffer.resize(terminal_size);
@@ -56,10 +70,20 @@ impl VideoSystem {
             dirty_pixels: BTreeMap::new(),
             frame_buffer,
             stdout,
+            text_input_enabled,
         })
     }
 
     pub fn draw(&mut self, frame: VideoFrame<&[u8]>) -> pagurus::Result<()> {
+        let region = Region::from(frame.spec().resolution);
+        self.draw_regions(frame, &[region]).or_fail()
+    }
+
+    pub fn draw_regions(
+        &mut self,
+        frame: VideoFrame<&[u8]>,
+        regions: &[Region],
+    ) -> pagurus::Result<()> {
         let terminal_size = Self::terminal_size().or_fail()?;
 
         if self.frame_buffer.size != terminal_size {
@@ -67,7 +91,14 @@ impl VideoSystem {
             return Ok(());
         }
 
-        self.draw_to_buffer(frame);
+        if self.frame_buffer.initialized {
+            for &region in regions {
+                self.draw_to_buffer(&frame, region);
+            }
+        } else {
+            self.draw_to_buffer(&frame, Region::from(frame.spec().resolution));
+            self.frame_buffer.initialized = true;
+        }
         self.draw_to_terminal().or_fail()?;
 
         Ok(())
@@ -81,10 +112,28 @@ impl VideoSystem {
         Ok(())
     }
 
+    pub fn set_text_input_enabled(&mut self, enabled: bool) -> pagurus::Result<()> {
+        self.text_input_enabled.store(enabled, Ordering::SeqCst);
+
+        // Bracketed paste mode lets pasted text be told apart from typed keys.
+        if enabled {
+            write!(self.stdout, "\x1b[?2004h").or_fail()?;
+        } else {
+            write!(self.stdout, "\x1b[?2004l").or_fail()?;
+        }
+        self.stdout.flush().or_fail()?;
+        Ok(())
+    }
+
     pub fn event_queue_mut(&mut self) -> &mut mpsc::Receiver<Event> {
         &mut self.event_queue
     }
 
+    #[cfg_attr(not(any(feature = "gamepad", unix)), allow(dead_code))]
+    pub fn event_sender(&self) -> mpsc::Sender<Event> {
+        self.event_sender.clone()
+    }
+
     fn resize_frame_buffer(&mut self, size: Size) -> pagurus::Result<()> {
         self.frame_buffer.resize(size);
         self.event_sender
@@ -94,7 +143,7 @@ impl VideoSystem {
         Ok(())
     }
 
-    fn terminal_size() -> pagurus::Result<Size> {
+    pub fn terminal_size() -> pagurus::Result<Size> {
         termion::terminal_size()
             .map(|(w, h)| Size::from_wh(w as u32, h as u32 * 2))
             .or_fail()
@@ -120,7 +169,7 @@ impl VideoSystem {
         Ok(())
     }
 
-    fn draw_to_buffer(&mut self, frame: VideoFrame<&[u8]>) {
+    fn draw_to_buffer(&mut self, frame: &VideoFrame<&[u8]>, region: Region) {
         let width = self
             .frame_buffer
             .size
@@ -131,10 +180,12 @@ impl VideoSystem {
             .size
             .height
             .min(frame.spec().resolution.height);
+        let region = region.intersection(Region::from(Size::from_wh(width, height)));
+        let (start, end) = (region.start(), region.end());
 
-        for y in 0..height {
+        for y in start.y as u32..end.y as u32 {
             let event_y = y / 2 * 2;
-            for x in 0..width {
+            for x in start.x as u32..end.x as u32 {
                 let position = Position::from_xy(x as i32, y as i32);
                 let old_rgb = self.frame_buffer.get_rgb(position);
                 let new_rgb = frame.read_rgb(position);
@@ -156,8 +207,16 @@ impl VideoSystem {
                 );
             }
         }
+    }
+}
 
-        self.frame_buffer.initialized = true;
+impl Drop for VideoSystem {
+    fn drop(&mut self) {
+        if self.text_input_enabled.load(Ordering::SeqCst) {
+            let _ = self.set_text_input_enabled(false);
+        }
+        let _ = write!(self.stdout, "{FOCUS_REPORTING_OFF}");
+        let _ = self.stdout.flush();
     }
 }
 
@@ -167,37 +226,88 @@ impl std::fmt::Debug for VideoSystem {
     }
 }
 
-fn listen_input_events(tx: mpsc::Sender<Event>) {
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+const FOCUS_REPORTING_ON: &str = "\x1b[?1004h";
+const FOCUS_REPORTING_OFF: &str = "\x1b[?1004l";
+const FOCUS_IN: &[u8] = b"\x1b[I";
+const FOCUS_OUT: &[u8] = b"\x1b[O";
+
+fn listen_input_events(tx: mpsc::Sender<Event>, text_input_enabled: Arc<AtomicBool>) {
     let mut mouse_state = MouseState::default();
+    let mut pasted_text: Option<String> = None;
     for event in std::io::stdin().events() {
         let Ok(event) = event else {
             break;
         };
 
+        let mut text_input = None;
+        match &event {
+            termion::event::Event::Unsupported(bytes) if bytes == PASTE_START => {
+                pasted_text = Some(String::new());
+                continue;
+            }
+            termion::event::Event::Unsupported(bytes) if bytes == PASTE_END => {
+                if let Some(text) = pasted_text.take() {
+                    if tx.send(Event::TextInput(text)).is_err() {
+                        break;
+                    }
+                }
+                continue;
+            }
+            termion::event::Event::Key(termion::event::Key::Char(c)) => {
+                if let Some(text) = &mut pasted_text {
+                    text.push(*c);
+                    continue;
+                }
+                if !c.is_control() && text_input_enabled.load(Ordering::SeqCst) {
+                    text_input = Some(Event::TextInput(c.to_string()));
+                }
+            }
+            _ if pasted_text.is_some() => continue,
+            _ => {}
+        }
+
         if let Some(event) = to_pagurus_event(&mut mouse_state, event) {
             if tx.send(event).is_err() {
                 break;
             }
         }
+        if let Some(event) = text_input {
+            if tx.send(event).is_err() {
+                break;
+            }
+        }
     }
 }
 
 fn to_pagurus_event(mouse_state: &mut MouseState, v: termion::event::Event) -> Option<Event> {
     match v {
+        // Terminals do not report key releases, so `Event::KeyUp` is never emitted.
         termion::event::Event::Key(v) => to_pagurus_key_event(v).map(Event::Key),
-        termion::event::Event::Mouse(v) => mouse_state
-            .convert_to_pagurus_mouse_event(v)
-            .map(Event::Mouse),
+        termion::event::Event::Mouse(v) => mouse_state.convert_to_pagurus_mouse_event(v),
+        termion::event::Event::Unsupported(bytes) if bytes == FOCUS_IN => {
+            Some(Event::FocusChanged(true))
+        }
+        termion::event::Event::Unsupported(bytes) if bytes == FOCUS_OUT => {
+            Some(Event::FocusChanged(false))
+        }
         termion::event::Event::Unsupported(_) => None,
     }
 }
 
 fn to_pagurus_key_event(v: termion::event::Key) -> Option<KeyEvent> {
-    fn char_to_key(c: char) -> Key {
-        match c {
+    fn char_to_key_event(c: char) -> KeyEvent {
+        let key = match c {
             '\n' => Key::Return,
             '\t' => Key::Tab,
+            ' ' => Key::Space,
             c => Key::Char(c),
+        };
+        KeyEvent {
+            shift: c.is_uppercase(),
+            ..key.into()
         }
     }
 
@@ -207,19 +317,26 @@ fn to_pagurus_key_event(v: termion::event::Key) -> Option<KeyEvent> {
         termion::event::Key::Right => Some(Key::Right.into()),
         termion::event::Key::Up => Some(Key::Up.into()),
         termion::event::Key::Down => Some(Key::Down.into()),
+        termion::event::Key::Home => Some(Key::Home.into()),
+        termion::event::Key::End => Some(Key::End.into()),
+        termion::event::Key::PageUp => Some(Key::PageUp.into()),
+        termion::event::Key::PageDown => Some(Key::PageDown.into()),
+        termion::event::Key::Insert => Some(Key::Insert.into()),
         termion::event::Key::Delete => Some(Key::Delete.into()),
+        termion::event::Key::F(n) => Some(Key::F(n).into()),
         termion::event::Key::Esc => Some(Key::Esc.into()),
-        termion::event::Key::BackTab => Some(Key::BackTab.into()),
-        termion::event::Key::Char(c) => Some(char_to_key(c).into()),
+        termion::event::Key::BackTab => Some(KeyEvent {
+            shift: true,
+            ..Key::BackTab.into()
+        }),
+        termion::event::Key::Char(c) => Some(char_to_key_event(c)),
         termion::event::Key::Ctrl(c) => Some(KeyEvent {
-            key: char_to_key(c),
             ctrl: true,
-            alt: false,
+            ..char_to_key_event(c)
         }),
         termion::event::Key::Alt(c) => Some(KeyEvent {
-            key: char_to_key(c),
-            ctrl: false,
             alt: true,
+            ..char_to_key_event(c)
         }),
         _ => None,
     }
@@ -227,38 +344,84 @@ fn to_pagurus_key_event(v: termion::event::Key) -> Option<KeyEvent> {
 
 #[derive(Debug, Default)]
 struct MouseState {
-    pressed: bool,
+    pressed: Option<MouseButton>,
 }
 
 impl MouseState {
-    fn convert_to_pagurus_mouse_event(
-        &mut self,
-        v: termion::event::MouseEvent,
-    ) -> Option<MouseEvent> {
+    // One wheel notch scrolls by a terminal row (two pixels).
+    const WHEEL_DELTA: i32 = 2;
+
+    fn convert_to_pagurus_mouse_event(&mut self, v: termion::event::MouseEvent) -> Option<Event> {
         fn position(x: u16, y: u16) -> Position {
             Position::from_xy(x as i32 - 1, (y as i32 - 1) * 2)
         }
 
+        fn pointer_event(
+            action: PointerAction,
+            button: Option<MouseButton>,
+            buttons: PointerButtons,
+            x: u16,
+            y: u16,
+        ) -> Event {
+            Event::Pointer(PointerEvent {
+                id: PointerId::new(0),
+                kind: PointerKind::Mouse,
+                action,
+                position: position(x, y),
+                buttons,
+                button,
+                is_primary: true,
+            })
+        }
+
         match v {
             termion::event::MouseEvent::Press(button, x, y) => {
-                self.pressed = false;
-                if button != termion::event::MouseButton::Left {
-                    return None;
-                }
-                self.pressed = true;
-                Some(MouseEvent::Down {
-                    position: position(x, y),
-                })
+                let button = match button {
+                    termion::event::MouseButton::Left => MouseButton::Left,
+                    termion::event::MouseButton::Right => MouseButton::Right,
+                    termion::event::MouseButton::Middle => MouseButton::Middle,
+                    termion::event::MouseButton::WheelUp => {
+                        return Some(Event::Wheel {
+                            delta_x: 0,
+                            delta_y: -Self::WHEEL_DELTA,
+                            position: position(x, y),
+                        });
+                    }
+                    termion::event::MouseButton::WheelDown => {
+                        return Some(Event::Wheel {
+                            delta_x: 0,
+                            delta_y: Self::WHEEL_DELTA,
+                            position: position(x, y),
+                        });
+                    }
+                };
+
+                // Terminals report only a single pressed button at a time.
+                self.pressed = Some(button);
+                let buttons = button.into();
+                Some(pointer_event(
+                    PointerAction::Down,
+                    Some(button),
+                    buttons,
+                    x,
+                    y,
+                ))
+            }
+            termion::event::MouseEvent::Release(x, y) => {
+                let button = self.pressed.take()?;
+                let buttons = PointerButtons::NONE;
+                Some(pointer_event(
+                    PointerAction::Up,
+                    Some(button),
+                    buttons,
+                    x,
+                    y,
+                ))
+            }
+            termion::event::MouseEvent::Hold(x, y) => {
+                let buttons = self.pressed?.into();
+                Some(pointer_event(PointerAction::Move, None, buttons, x, y))
             }
-            termion::event::MouseEvent::Release(x, y) => self.pressed.then(|| {
-                self.pressed = false;
-                MouseEvent::Up {
-                    position: position(x, y),
-                }
-            }),
-            termion::event::MouseEvent::Hold(x, y) => self.pressed.then(|| MouseEvent::Move {
-                position: position(x, y),
-            }),
         }
     }
 }
@@ -1,7 +1,8 @@
 const AUDIO_WORKLET_PROCESSOR_CODE = `
 class PagurusAudioWorkletProcessor extends AudioWorkletProcessor {
-  constructor() {
+  constructor(options) {
     super();
+    this.channels = options.processorOptions.channels;
     this.inputBuffer = [];
     this.offset = 0;
     this.port.onmessage = (e) => {
@@ -10,15 +11,20 @@ class PagurusAudioWorkletProcessor extends AudioWorkletProcessor {
   }
 
   process(inputs, outputs, parameters) {
-    const outputChannel = outputs[0][0];
-    for (let i = 0; i < outputChannel.length; i++) {
+    const output = outputs[0];
+    for (let i = 0; i < output[0].length; i++) {
       const audioData = this.inputBuffer[0];
-      if (audioData === undefined) {
-        outputChannel[i] = 0;
-      } else {
-        outputChannel[i] = audioData[this.offset];
-        this.offset++;
-        if (this.offset == audioData.length) {
+      for (let c = 0; c < output.length; c++) {
+        if (audioData === undefined || c >= this.channels) {
+          output[c][i] = 0;
+        } else {
+          output[c][i] = audioData[this.offset + c];
+        }
+      }
+      if (audioData !== undefined) {
+        // The samples of each frame are interleaved.
+        this.offset += this.channels;
+        if (this.offset >= audioData.length) {
           this.inputBuffer.shift();
           this.offset = 0;
         }
@@ -39,17 +45,114 @@ type Position = { x: number; y: number };
 type Event =
   | { timeout: TimeoutTag }
   | { key: PagurusKeyEvent }
-  | { mouse: PagurusMouseEvent }
-  | { windowResized: Size };
+  | { keyUp: PagurusKeyEvent }
+  | { textInput: string }
+  | { composition: PagurusCompositionEvent }
+  | { focusChanged: boolean }
+  | "suspended"
+  | "resumed"
+  | "terminateRequested"
+  | { pointer: PagurusPointerEvent }
+  | { windowResized: Size }
+  | { gamepad: PagurusGamepadEvent }
+  | { wheel: { deltaX: number; deltaY: number; position: Position } };
 
 type TimeoutTag = number;
 
-type PagurusKeyEvent = { key: Key; ctrl: boolean; alt: boolean };
+type PagurusGamepadEvent =
+  | { connected: { id: number } }
+  | { disconnected: { id: number } }
+  | { buttonDown: { id: number; button: PagurusGamepadButton } }
+  | { buttonUp: { id: number; button: PagurusGamepadButton } }
+  | { axisMoved: { id: number; axis: PagurusGamepadAxis; value: number } };
+
+type PagurusGamepadButton =
+  | { other: number }
+  | "south"
+  | "east"
+  | "west"
+  | "north"
+  | "leftShoulder"
+  | "rightShoulder"
+  | "leftTrigger"
+  | "rightTrigger"
+  | "select"
+  | "start"
+  | "leftStick"
+  | "rightStick"
+  | "dPadUp"
+  | "dPadDown"
+  | "dPadLeft"
+  | "dPadRight"
+  | "home";
+
+type PagurusGamepadAxis = { other: number } | "leftStickX" | "leftStickY" | "rightStickX" | "rightStickY";
+
+// See: https://w3c.github.io/gamepad/#remapping
+const STANDARD_GAMEPAD_BUTTONS: PagurusGamepadButton[] = [
+  "south",
+  "east",
+  "west",
+  "north",
+  "leftShoulder",
+  "rightShoulder",
+  "leftTrigger",
+  "rightTrigger",
+  "select",
+  "start",
+  "leftStick",
+  "rightStick",
+  "dPadUp",
+  "dPadDown",
+  "dPadLeft",
+  "dPadRight",
+  "home",
+];
+
+const STANDARD_GAMEPAD_AXES: PagurusGamepadAxis[] = ["leftStickX", "leftStickY", "rightStickX", "rightStickY"];
+
+function toPagurusGamepadButton(gamepad: Gamepad, index: number): PagurusGamepadButton {
+  if (gamepad.mapping === "standard" && index < STANDARD_GAMEPAD_BUTTONS.length) {
+    return STANDARD_GAMEPAD_BUTTONS[index];
+  }
+  return { other: index };
+}
+
+function toPagurusGamepadAxis(gamepad: Gamepad, index: number): PagurusGamepadAxis {
+  if (gamepad.mapping === "standard" && index < STANDARD_GAMEPAD_AXES.length) {
+    return STANDARD_GAMEPAD_AXES[index];
+  }
+  return { other: index };
+}
 
-type PagurusMouseEvent =
-  | { move: { position: Position } }
-  | { down: { position: Position } }
-  | { up: { position: Position } };
+type PagurusCompositionEvent = "start" | { update: { text: string } } | { end: { text: string } };
+
+type PagurusKeyEvent = { key: Key; ctrl: boolean; alt: boolean; shift: boolean; meta: boolean };
+
+type PagurusPointerEvent = {
+  id: number;
+  kind: "mouse" | "touch" | "pen";
+  action: "move" | "down" | "up" | "cancel";
+  position: Position;
+  buttons: number;
+  button: PagurusMouseButton | null;
+  isPrimary: boolean;
+};
+
+type PagurusMouseButton = "left" | "right" | "middle";
+
+function toPagurusMouseButton(button: number): PagurusMouseButton | null {
+  switch (button) {
+    case 0:
+      return "left";
+    case 1:
+      return "middle";
+    case 2:
+      return "right";
+    default:
+      return null;
+  }
+}
 
 type Key =
   | { char: string }
@@ -65,7 +168,14 @@ type Key =
   | "alt"
   | "tab"
   | "backTab"
-  | "esc";
+  | "esc"
+  | "home"
+  | "end"
+  | "pageUp"
+  | "pageDown"
+  | "insert"
+  | "space"
+  | { f: number };
 
 function toPagurusKey(key: string): Key | undefined {
   switch (key) {
@@ -87,7 +197,22 @@ function toPagurusKey(key: string): Key | undefined {
       return "tab";
     case "Escape":
       return "esc";
+    case "Home":
+      return "home";
+    case "End":
+      return "end";
+    case "PageUp":
+      return "pageUp";
+    case "PageDown":
+      return "pageDown";
+    case "Insert":
+      return "insert";
+    case " ":
+      return "space";
     default:
+      if (/^F([1-9]|1[0-2])$/.test(key)) {
+        return { f: Number(key.slice(1)) };
+      }
       if (isSpecialKey(key)) {
         return;
       }
@@ -105,6 +230,8 @@ interface SystemOptions {
   disableTouchEvents?: boolean;
   disableMouseEvents?: boolean;
   disableKeyEvents?: boolean;
+  disableGamepadEvents?: boolean;
+  storageKeyPrefix?: string;
 }
 
 class System {
@@ -113,10 +240,17 @@ class System {
   private audioContext?: AudioContext;
   private audioInputNode?: AudioWorkletNode;
   private audioSampleRate?: number;
+  private audioChannels = 1;
   private startTime: number;
   private eventQueue: Event[];
   private resolveNextEvent?: (event: Event) => void;
   private propagateControlKey: boolean;
+  private storageKeyPrefix: string;
+  private pendingTimeouts: Map<TimeoutTag, ReturnType<typeof setTimeout>> = new Map();
+  private gamepadStates: Map<number, { buttons: boolean[]; axes: number[] }> = new Map();
+  private textInput?: HTMLInputElement;
+  private textInputEnabled = false;
+  private capabilityFlags = 0;
 
   static create(wasmMemory: WebAssembly.Memory, options: SystemOptions = {}): System {
     return new System(wasmMemory, options.canvas, options);
@@ -125,6 +259,7 @@ class System {
   private constructor(wasmMemory: WebAssembly.Memory, canvas: HTMLCanvasElement | undefined, options: SystemOptions) {
     this.wasmMemory = wasmMemory;
     this.propagateControlKey = !(options.propagateControlKey === false);
+    this.storageKeyPrefix = options.storageKeyPrefix || "pagurus:";
 
     let canvasSize = { width: 0, height: 0 };
     this.canvas = canvas;
@@ -136,48 +271,107 @@ class System {
 
     this.startTime = performance.now();
 
+    // See `CAPABILITY_*` constants in `src/wasm.rs`.
     if (this.canvas !== undefined) {
+      this.capabilityFlags |= 1 << 0; // video
+      this.capabilityFlags |= 1 << 5; // true color
       if (!(options.disableKeyEvents === true)) {
-        document.addEventListener("keyup", (event) => {
-          this.handleKeyup(event);
+        this.capabilityFlags |= 1 << 2; // keyboard
+      }
+      if (!(options.disableMouseEvents === true) || !(options.disableTouchEvents === true)) {
+        this.capabilityFlags |= 1 << 3; // pointer
+      }
+    }
+    if (typeof AudioContext !== "undefined") {
+      this.capabilityFlags |= 1 << 1; // audio
+    }
+    if (!(options.disableGamepadEvents === true) && typeof navigator.getGamepads === "function") {
+      this.capabilityFlags |= 1 << 4; // gamepad
+    }
+
+    if (this.canvas !== undefined) {
+      if (!(options.disableKeyEvents === true)) {
+        document.addEventListener("keydown", (event) => {
+          if (event.isComposing) {
+            return;
+          }
+          this.handleKeyEvent(event, false);
           this.preventKeyEventDefaultIfNeed(event);
         });
-        document.addEventListener("keydown", (event) => {
+        document.addEventListener("keyup", (event) => {
+          if (event.isComposing) {
+            return;
+          }
+          this.handleKeyEvent(event, true);
           this.preventKeyEventDefaultIfNeed(event);
         });
       }
 
-      if (!(options.disableMouseEvents === true)) {
-        this.canvas.addEventListener("mousemove", (event) => {
-          this.handleMousemove(event);
-        });
-        this.canvas.addEventListener("mousedown", (event) => {
-          this.handleMousedown(event);
-        });
-        this.canvas.addEventListener("mouseup", (event) => {
-          this.handleMouseup(event);
+      const disableMouse = options.disableMouseEvents === true;
+      const disableTouch = options.disableTouchEvents === true;
+      if (!disableMouse || !disableTouch) {
+        const isEnabled = (event: PointerEvent) => {
+          if (event.pointerType === "mouse") {
+            return !disableMouse;
+          } else {
+            return !disableTouch;
+          }
+        };
+        const handlePointerEvent = (event: PointerEvent, action: PagurusPointerEvent["action"]) => {
+          if (isEnabled(event)) {
+            this.handlePointerEvent(event, action);
+            event.stopPropagation();
+            event.preventDefault();
+          }
+        };
+
+        // Prevents the browser from scrolling or zooming while the canvas is touched.
+        this.canvas.style.touchAction = "none";
+        this.canvas.addEventListener("pointermove", (event) => handlePointerEvent(event, "move"));
+        this.canvas.addEventListener("pointerdown", (event) => {
+          if (isEnabled(event) && this.canvas !== undefined) {
+            this.canvas.setPointerCapture(event.pointerId);
+          }
+          if (this.textInputEnabled) {
+            this.textInput?.focus();
+          }
+          handlePointerEvent(event, "down");
         });
+        this.canvas.addEventListener("pointerup", (event) => handlePointerEvent(event, "up"));
+        this.canvas.addEventListener("pointercancel", (event) => handlePointerEvent(event, "cancel"));
       }
 
-      if (!(options.disableTouchEvents === true)) {
-        this.canvas.addEventListener("touchmove", (event) => {
-          this.handleTouchmove(event);
-          event.stopPropagation();
-          event.preventDefault();
-        });
-        this.canvas.addEventListener("touchstart", (event) => {
-          this.handleTouchstart(event);
+      if (!disableMouse) {
+        this.canvas.addEventListener("wheel", (event) => {
+          this.handleWheel(event);
           event.stopPropagation();
           event.preventDefault();
         });
-        this.canvas.addEventListener("touchend", (event) => {
-          this.handleTouchend(event);
-          event.stopPropagation();
+        this.canvas.addEventListener("contextmenu", (event) => {
           event.preventDefault();
         });
       }
     }
 
+    window.addEventListener("focus", () => {
+      this.enqueueEvent({ focusChanged: true });
+    });
+    window.addEventListener("blur", () => {
+      this.enqueueEvent({ focusChanged: false });
+    });
+    document.addEventListener("visibilitychange", () => {
+      this.enqueueEvent(document.hidden ? "suspended" : "resumed");
+    });
+
+    if (!(options.disableGamepadEvents === true)) {
+      window.addEventListener("gamepadconnected", (event) => {
+        this.handleGamepadconnected(event);
+      });
+      window.addEventListener("gamepaddisconnected", (event) => {
+        this.handleGamepaddisconnected(event);
+      });
+    }
+
     const initialEvent = { windowResized: canvasSize };
     this.eventQueue = [initialEvent];
   }
@@ -194,6 +388,10 @@ class System {
   }
 
   private preventKeyEventDefaultIfNeed(event: KeyboardEvent): void {
+    if (this.textInputEnabled && (event.key.length === 1 || event.key === "Dead")) {
+      // Lets the hidden text input element receive the typed characters.
+      return;
+    }
     if (this.propagateControlKey) {
       if (event.ctrlKey || event.key == "Control") {
         return;
@@ -204,77 +402,171 @@ class System {
     event.preventDefault();
   }
 
-  private handleKeyup(event: KeyboardEvent) {
-    const key = toPagurusKey(event.key);
+  private handleKeyEvent(event: KeyboardEvent, isUp: boolean) {
+    let key = toPagurusKey(event.key);
     if (key !== undefined) {
-      const ctrl = event.ctrlKey;
-      const alt = event.altKey;
       if (key == "tab" && event.shiftKey) {
-        this.enqueueEvent({ key: { key: "backTab", ctrl, alt } });
+        key = "backTab";
+      }
+      const keyEvent = { key, ctrl: event.ctrlKey, alt: event.altKey, shift: event.shiftKey, meta: event.metaKey };
+      if (isUp) {
+        this.enqueueEvent({ keyUp: keyEvent });
       } else {
-        this.enqueueEvent({ key: { key, ctrl, alt } });
+        this.enqueueEvent({ key: keyEvent });
       }
     }
   }
 
-  private touchPosition(touch: Touch): Position {
+  private handlePointerEvent(event: PointerEvent, action: PagurusPointerEvent["action"]) {
     if (this.canvas === undefined) {
       throw new Error("bug");
     }
     const rect = this.canvas.getBoundingClientRect();
-    return { x: Math.round(touch.clientX - rect.left), y: Math.round(touch.clientY - rect.top) };
+    const position = { x: Math.round(event.clientX - rect.left), y: Math.round(event.clientY - rect.top) };
+    let kind: PagurusPointerEvent["kind"] = "mouse";
+    if (event.pointerType === "touch" || event.pointerType === "pen") {
+      kind = event.pointerType;
+    }
+    this.enqueueEvent({
+      pointer: {
+        id: event.pointerId,
+        kind,
+        action,
+        position,
+        buttons: event.buttons & 0b111,
+        button: action === "down" || action === "up" ? toPagurusMouseButton(event.button) : null,
+        isPrimary: event.isPrimary,
+      },
+    });
   }
 
-  private handleTouchmove(event: TouchEvent) {
-    const touches = event.changedTouches;
-    for (let i = 0; i < touches.length; i++) {
-      const touch = touches[i];
-      const position = this.touchPosition(touch);
-      this.enqueueEvent({ mouse: { move: { position } } });
-      break;
+  private handleWheel(event: WheelEvent) {
+    if (this.canvas === undefined) {
+      throw new Error("bug");
+    }
+    let scale = 1;
+    if (event.deltaMode === WheelEvent.DOM_DELTA_LINE) {
+      scale = 16;
+    } else if (event.deltaMode === WheelEvent.DOM_DELTA_PAGE) {
+      scale = this.canvas.height;
     }
+    const rect = this.canvas.getBoundingClientRect();
+    const position = { x: Math.round(event.clientX - rect.left), y: Math.round(event.clientY - rect.top) };
+    const deltaX = Math.round(event.deltaX * scale);
+    const deltaY = Math.round(event.deltaY * scale);
+    this.enqueueEvent({ wheel: { deltaX, deltaY, position } });
   }
 
-  private handleTouchstart(event: TouchEvent) {
-    const touches = event.changedTouches;
-    for (let i = 0; i < touches.length; i++) {
-      const touch = touches[i];
-      const position = this.touchPosition(touch);
-      this.enqueueEvent({ mouse: { down: { position } } });
-      break;
+  private handleGamepadconnected(event: GamepadEvent) {
+    const id = event.gamepad.index;
+    const wasPolling = this.gamepadStates.size > 0;
+    this.gamepadStates.set(id, {
+      buttons: event.gamepad.buttons.map((button) => button.pressed),
+      axes: event.gamepad.axes.map((value) => Math.round(value * 32767)),
+    });
+    this.enqueueEvent({ gamepad: { connected: { id } } });
+    if (!wasPolling) {
+      requestAnimationFrame(() => this.pollGamepads());
     }
   }
 
-  private handleTouchend(event: TouchEvent) {
-    const touches = event.changedTouches;
-    for (let i = 0; i < touches.length; i++) {
-      const touch = touches[i];
-      const position = this.touchPosition(touch);
-      this.enqueueEvent({ mouse: { up: { position } } });
-      break;
+  private handleGamepaddisconnected(event: GamepadEvent) {
+    const id = event.gamepad.index;
+    if (this.gamepadStates.delete(id)) {
+      this.enqueueEvent({ gamepad: { disconnected: { id } } });
     }
   }
 
-  private handleMousemove(event: MouseEvent) {
-    const x = event.offsetX;
-    const y = event.offsetY;
-    this.enqueueEvent({ mouse: { move: { position: { x, y } } } });
+  // The Gamepad API has no input events, so the states are polled on every animation frame.
+  private pollGamepads() {
+    if (this.gamepadStates.size === 0) {
+      return;
+    }
+
+    for (const gamepad of navigator.getGamepads()) {
+      if (gamepad === null) {
+        continue;
+      }
+      const id = gamepad.index;
+      const state = this.gamepadStates.get(id);
+      if (state === undefined) {
+        continue;
+      }
+
+      gamepad.buttons.forEach((button, i) => {
+        if (button.pressed !== state.buttons[i]) {
+          state.buttons[i] = button.pressed;
+          const pagurusButton = toPagurusGamepadButton(gamepad, i);
+          if (button.pressed) {
+            this.enqueueEvent({ gamepad: { buttonDown: { id, button: pagurusButton } } });
+          } else {
+            this.enqueueEvent({ gamepad: { buttonUp: { id, button: pagurusButton } } });
+          }
+        }
+      });
+      gamepad.axes.forEach((axisValue, i) => {
+        const value = Math.round(axisValue * 32767);
+        if (value !== state.axes[i]) {
+          state.axes[i] = value;
+          const axis = toPagurusGamepadAxis(gamepad, i);
+          this.enqueueEvent({ gamepad: { axisMoved: { id, axis, value } } });
+        }
+      });
+    }
+
+    requestAnimationFrame(() => this.pollGamepads());
   }
 
-  private handleMousedown(event: MouseEvent) {
-    const x = event.offsetX;
-    const y = event.offsetY;
-    if (event.button === 0) {
-      this.enqueueEvent({ mouse: { down: { position: { x, y } } } });
+  textInputSetEnabled(enabled: boolean) {
+    this.textInputEnabled = enabled;
+    if (!enabled) {
+      this.textInput?.blur();
+      return;
     }
+    if (this.textInput === undefined) {
+      this.textInput = this.createTextInput();
+    }
+    this.textInput.focus();
   }
 
-  private handleMouseup(event: MouseEvent) {
-    const x = event.offsetX;
-    const y = event.offsetY;
-    if (event.button === 0) {
-      this.enqueueEvent({ mouse: { up: { position: { x, y } } } });
+  private createTextInput(): HTMLInputElement {
+    const input = document.createElement("input");
+    input.type = "text";
+    input.autocomplete = "off";
+    input.style.position = "fixed";
+    input.style.opacity = "0";
+    input.style.pointerEvents = "none";
+    if (this.canvas !== undefined) {
+      // Places the element over the canvas so that IME candidate windows appear nearby.
+      const rect = this.canvas.getBoundingClientRect();
+      input.style.left = `${rect.left}px`;
+      input.style.top = `${rect.top}px`;
     }
+    document.body.appendChild(input);
+
+    const flush = () => {
+      const text = input.value;
+      input.value = "";
+      if (text !== "") {
+        this.enqueueEvent({ textInput: text });
+      }
+    };
+    input.addEventListener("input", (event) => {
+      if (!(event as InputEvent).isComposing) {
+        flush();
+      }
+    });
+    input.addEventListener("compositionstart", () => {
+      this.enqueueEvent({ composition: "start" });
+    });
+    input.addEventListener("compositionupdate", (event) => {
+      this.enqueueEvent({ composition: { update: { text: event.data } } });
+    });
+    input.addEventListener("compositionend", (event) => {
+      this.enqueueEvent({ composition: { end: { text: event.data } } });
+      flush();
+    });
+    return input;
   }
 
   private enqueueEvent(event: Event) {
@@ -286,6 +578,10 @@ class System {
     }
   }
 
+  requestTerminate() {
+    this.enqueueEvent("terminateRequested");
+  }
+
   requestRedraw() {
     if (this.canvas === undefined) {
       return;
@@ -302,11 +598,41 @@ class System {
   }
 
   videoDraw(videoFrameOffset: number, videoFrameLen: number, width: number, stride: number, format: number) {
+    this.drawVideoFrame(videoFrameOffset, videoFrameLen, width, stride, format, undefined);
+  }
+
+  videoDrawRegions(
+    videoFrameOffset: number,
+    videoFrameLen: number,
+    width: number,
+    stride: number,
+    format: number,
+    regionsPtr: number,
+    regionsLen: number
+  ) {
+    // Each region consists of four u32 values (x, y, width and height).
+    const view = new DataView(this.wasmMemory.buffer);
+    const regions = [];
+    for (let i = 0; i < regionsLen; i++) {
+      const offset = regionsPtr + i * 16;
+      regions.push([0, 4, 8, 12].map((j) => view.getUint32(offset + j, true)));
+    }
+    this.drawVideoFrame(videoFrameOffset, videoFrameLen, width, stride, format, regions);
+  }
+
+  private drawVideoFrame(
+    videoFrameOffset: number,
+    videoFrameLen: number,
+    width: number,
+    stride: number,
+    format: number,
+    regions: number[][] | undefined
+  ) {
     if (this.canvas === undefined) {
       return;
     }
-    if (format != 1) {
-      throw new Error(`expected RGB32(3) format, but got ${format}`);
+    if (format != 1 && format != 4) {
+      throw new Error(`expected RGB32(1) or RGBA32(4) format, but got ${format}`);
     }
     if (width != stride) {
       throw new Error(`width ${width} differs from stride ${stride}`);
@@ -323,17 +649,31 @@ class System {
 
     const height = videoFrameLen / 4 / width;
     const videoFrame = new Uint8ClampedArray(this.wasmMemory.buffer, videoFrameOffset, videoFrameLen);
-    if (width != this.canvas.width || height != this.canvas.height) {
+    const resized = width != this.canvas.width || height != this.canvas.height;
+    if (resized) {
       this.canvas.width = width;
       this.canvas.height = height;
     }
 
     const image = new ImageData(videoFrame, width, height);
-    canvasCtx.putImageData(image, 0, 0);
+    if (regions === undefined || resized) {
+      // Resizing clears the canvas, so the whole frame needs to be drawn.
+      canvasCtx.putImageData(image, 0, 0);
+      return;
+    }
+    for (const [x, y, w, h] of regions) {
+      canvasCtx.putImageData(image, 0, 0, x, y, w, h);
+    }
   }
 
-  audioInit(sampleRate: number, _dataSamples: number, sampleFormatPtr: number) {
+  audioInit(sampleRate: number, _dataSamples: number, sampleFormatPtr: number, channelsPtr: number) {
     this.audioSampleRate = sampleRate;
+
+    // Only mono and stereo are supported.
+    const view = new DataView(this.wasmMemory.buffer);
+    this.audioChannels = Math.min(Math.max(view.getUint8(channelsPtr), 1), 2);
+    view.setUint8(channelsPtr, this.audioChannels);
+
     const littleEndian = (function () {
       const buffer = new ArrayBuffer(2);
       new DataView(buffer).setInt16(0, 256, true);
@@ -346,6 +686,22 @@ class System {
     }
   }
 
+  capabilities(
+    flagsPtr: number,
+    maxWidthPtr: number,
+    maxHeightPtr: number,
+    pixelFormatsPtr: number,
+    sampleFormatsPtr: number
+  ) {
+    const littleEndian = new Uint8Array(new Uint16Array([1]).buffer)[0] === 1;
+    const view = new DataView(this.wasmMemory.buffer);
+    view.setUint32(flagsPtr, this.capabilityFlags, true);
+    view.setUint32(maxWidthPtr, 0, true); // 0=unknown
+    view.setUint32(maxHeightPtr, 0, true);
+    view.setUint32(pixelFormatsPtr, (1 << 1) | (1 << 4), true); // 1=RGB32, 4=RGBA32
+    view.setUint32(sampleFormatsPtr, 1 << (littleEndian ? 3 : 2), true); // 3=F32Le, 2=F32Be
+  }
+
   audioEnqueue(audioDataOffset: number, audioDataLen: number) {
     if (this.audioSampleRate === undefined) {
       console.warn("audioInit() has not been called yet");
@@ -360,7 +716,10 @@ class System {
       this.audioContext.audioWorklet
         .addModule(URL.createObjectURL(blob))
         .then(() => {
-          this.audioInputNode = new AudioWorkletNode(audioContext, AUDIO_WORKLET_PROCESSOR_NAME);
+          this.audioInputNode = new AudioWorkletNode(audioContext, AUDIO_WORKLET_PROCESSOR_NAME, {
+            outputChannelCount: [this.audioChannels],
+            processorOptions: { channels: this.audioChannels },
+          });
           this.audioInputNode.connect(audioContext.destination);
           this.audioInputNode.port.postMessage(data, [data.buffer]);
         })
@@ -386,9 +745,62 @@ class System {
   }
 
   clockSetTimeout(tag: TimeoutTag, timeout: number) {
-    setTimeout(() => {
+    this.clockCancelTimeout(tag);
+    const timeoutId = setTimeout(() => {
+      this.pendingTimeouts.delete(tag);
       this.enqueueEvent({ timeout: tag });
     }, timeout * 1000);
+    this.pendingTimeouts.set(tag, timeoutId);
+  }
+
+  clockCancelTimeout(tag: TimeoutTag) {
+    const timeoutId = this.pendingTimeouts.get(tag);
+    if (timeoutId !== undefined) {
+      clearTimeout(timeoutId);
+      this.pendingTimeouts.delete(tag);
+    }
+  }
+
+  storageGetSize(keyOffset: number, keyLen: number): number {
+    const value = this.storageGetBytes(keyOffset, keyLen);
+    return value === undefined ? -1 : value.length;
+  }
+
+  storageGet(keyOffset: number, keyLen: number, dataOffset: number) {
+    const value = this.storageGetBytes(keyOffset, keyLen);
+    if (value === undefined) {
+      throw new Error("storage entry disappeared during a read");
+    }
+    new Uint8Array(this.wasmMemory.buffer, dataOffset, value.length).set(value);
+  }
+
+  storagePut(keyOffset: number, keyLen: number, dataOffset: number, dataLen: number) {
+    const key = this.storageKeyPrefix + this.getWasmString(keyOffset, keyLen);
+    const data = new Uint8Array(this.wasmMemory.buffer, dataOffset, dataLen);
+    let binary = "";
+    for (let i = 0; i < data.length; i++) {
+      binary += String.fromCharCode(data[i]);
+    }
+    localStorage.setItem(key, btoa(binary));
+  }
+
+  storageDelete(keyOffset: number, keyLen: number) {
+    const key = this.storageKeyPrefix + this.getWasmString(keyOffset, keyLen);
+    localStorage.removeItem(key);
+  }
+
+  private storageGetBytes(keyOffset: number, keyLen: number): Uint8Array | undefined {
+    const key = this.storageKeyPrefix + this.getWasmString(keyOffset, keyLen);
+    const value = localStorage.getItem(key);
+    if (value === null) {
+      return undefined;
+    }
+    const binary = atob(value);
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+      bytes[i] = binary.charCodeAt(i);
+    }
+    return bytes;
   }
 
   private getWasmString(offset: number, len: number): string {
@@ -429,8 +841,27 @@ class Game {
         ) {
           systemRef.getSystem().videoDraw(videoFrameOffset, videoFrameLen, width, stride, format);
         },
-        systemAudioInit(sampleRate: number, dataSamples: number, sampleFormatPtr: number) {
-          systemRef.getSystem().audioInit(sampleRate, dataSamples, sampleFormatPtr);
+        systemVideoDrawRegions(
+          videoFrameOffset: number,
+          videoFrameLen: number,
+          width: number,
+          stride: number,
+          format: number,
+          regionsPtr: number,
+          regionsLen: number
+        ) {
+          systemRef.getSystem().videoDrawRegions(
+            videoFrameOffset,
+            videoFrameLen,
+            width,
+            stride,
+            format,
+            regionsPtr,
+            regionsLen
+          );
+        },
+        systemAudioInit(sampleRate: number, dataSamples: number, sampleFormatPtr: number, channelsPtr: number) {
+          systemRef.getSystem().audioInit(sampleRate, dataSamples, sampleFormatPtr, channelsPtr);
         },
         systemAudioEnqueue(dataOffset: number, dataLen: number) {
           systemRef.getSystem().audioEnqueue(dataOffset, dataLen);
@@ -444,6 +875,33 @@ class Game {
         systemClockSetTimeout(tag: number, timeout: number) {
           systemRef.getSystem().clockSetTimeout(tag, timeout);
         },
+        systemClockCancelTimeout(tag: number) {
+          systemRef.getSystem().clockCancelTimeout(tag);
+        },
+        systemStorageGetSize(keyOffset: number, keyLen: number): number {
+          return systemRef.getSystem().storageGetSize(keyOffset, keyLen);
+        },
+        systemStorageGet(keyOffset: number, keyLen: number, dataOffset: number) {
+          systemRef.getSystem().storageGet(keyOffset, keyLen, dataOffset);
+        },
+        systemStoragePut(keyOffset: number, keyLen: number, dataOffset: number, dataLen: number) {
+          systemRef.getSystem().storagePut(keyOffset, keyLen, dataOffset, dataLen);
+        },
+        systemStorageDelete(keyOffset: number, keyLen: number) {
+          systemRef.getSystem().storageDelete(keyOffset, keyLen);
+        },
+        systemTextInputSetEnabled(enabled: number) {
+          systemRef.getSystem().textInputSetEnabled(enabled !== 0);
+        },
+        systemCapabilities(
+          flagsPtr: number,
+          maxWidthPtr: number,
+          maxHeightPtr: number,
+          pixelFormatsPtr: number,
+          sampleFormatsPtr: number
+        ) {
+          systemRef.getSystem().capabilities(flagsPtr, maxWidthPtr, maxHeightPtr, pixelFormatsPtr, sampleFormatsPtr);
+        },
       },
     };
     const results = await WebAssembly.instantiateStreaming(fetch(gameWasmPath), importObject);
@@ -509,6 +967,10 @@ class Game {
     }
   }
 
+  exitCode(): number {
+    return (this.wasmInstance.exports.gameExitCode as CallableFunction)(this.gameInstance) as number;
+  }
+
   command(system: System, name: string, data: Uint8Array) {
     this.systemRef.setSystem(system);
 